@@ -0,0 +1,182 @@
+use chrono::{Local, TimeZone};
+use ratatui::{
+    style::{Color, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, List, ListItem},
+};
+use crossterm::event::{KeyCode, KeyEvent};
+use crate::jview_config::settings;
+use crate::jview_debug;
+use crate::jview_source::{BootInfo, LogSource};
+
+/// Popup listing the boots in the journal, like `journalctl --list-boots`.
+///
+/// The first row shows all boots again; picking a boot restricts the logs pane to
+/// it through `settings::set_boot`, the way the unit selector does with the unit.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JviewBoots {
+    boots: Vec<BootInfo>, // Oldest first
+    selected_idx: usize,  // Row under the cursor, 0 being "All boots"
+    vertical_start: usize,
+    max_viewer_height: usize,
+    error: Option<String>, // Why the boots could not be listed
+}
+
+impl JviewBoots {
+    pub fn new(source: &dyn LogSource) -> Self {
+        let (boots, error) = match source.list_boots(settings::is_user_scope(), settings::get_namespace().as_deref()) {
+            Ok(boots) => (boots, None),
+            Err(e) => (Vec::new(), Some(e.describe())),
+        };
+
+        // Start on the boot that is shown now
+        let selected_idx = match settings::get_boot() {
+            Some(boot) => boots.iter().position(|b| is_boot(b, &boot)).map_or(0, |i| i + 1),
+            None => 0,
+        };
+
+        JviewBoots {
+            boots,
+            selected_idx,
+            vertical_start: 0,
+            max_viewer_height: 25,
+            error,
+        }
+    }
+
+    pub fn set_max_height(&mut self, h: usize) {
+        self.max_viewer_height = h;
+    }
+
+    /// Number of rows that fit inside the borders of the popup.
+    fn visible_rows(&self) -> usize {
+        self.max_viewer_height.saturating_sub(2).max(1)
+    }
+
+    /// Handles a key while the popup is open.
+    ///
+    /// # Returns
+    ///
+    /// `false` once the popup should be closed.
+    pub fn navigate(&mut self, key: KeyEvent) -> bool {
+        let last = self.boots.len();
+        match key.code {
+            KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('b') => return false,
+            KeyCode::Up => self.selected_idx = self.selected_idx.saturating_sub(1),
+            KeyCode::Down => self.selected_idx = (self.selected_idx + 1).min(last),
+            KeyCode::Home => self.selected_idx = 0,
+            KeyCode::End => self.selected_idx = last,
+            KeyCode::Enter => {
+                match self.selected_idx.checked_sub(1).and_then(|i| self.boots.get(i)) {
+                    Some(boot) => select_boot(boot),
+                    None => settings::set_boot(None),
+                }
+                return false;
+            }
+            _ => {}
+        }
+
+        if self.selected_idx < self.vertical_start {
+            self.vertical_start = self.selected_idx;
+        } else if self.selected_idx >= self.vertical_start + self.visible_rows() {
+            self.vertical_start = self.selected_idx + 1 - self.visible_rows();
+        }
+        true
+    }
+
+    /// Creates the popup widget.
+    ///
+    /// # Returns
+    ///
+    /// A `List` widget with one row per boot, with its index, ID and time span.
+    pub fn get_boots_widget(&self) -> List<'static> {
+        let current = settings::get_boot();
+        let mut rows = vec![(current.is_none(), "All boots".to_string())];
+        for boot in &self.boots {
+            let shown = current.as_deref().is_some_and(|c| is_boot(boot, c));
+            rows.push((
+                shown,
+                format!(
+                    "{:>4} {} {} \u{2014} {}",
+                    boot.index,
+                    boot.id,
+                    format_usec(boot.first_usec),
+                    format_usec(boot.last_usec)
+                ),
+            ));
+        }
+
+        let items: Vec<ListItem> = rows
+            .into_iter()
+            .enumerate()
+            .skip(self.vertical_start)
+            .take(self.visible_rows())
+            .map(|(i, (shown, text))| {
+                let mark = if shown { "\u{2713} " } else { "  " };
+                let item = ListItem::new(format!("{}{}", mark, text));
+                if i == self.selected_idx {
+                    item.style(Style::default().fg(Color::Black).bg(Color::Cyan))
+                } else {
+                    item
+                }
+            })
+            .collect();
+
+        let mut title = vec![Span::raw(format!(
+            "Boots: {} [Enter]: Show | [Esc]: Close",
+            self.boots.len()
+        ))];
+        if let Some(e) = &self.error {
+            title.push(Span::styled(format!(" {} ", e), Style::default().fg(Color::White).bg(Color::Red)));
+        }
+
+        List::new(items)
+            .block(Block::default().borders(Borders::ALL).title(Line::from(title)))
+            .style(Style::default().fg(Color::White).bg(Color::Black))
+    }
+}
+
+/// Whether a `--boot` value in the settings names this boot.
+fn is_boot(boot: &BootInfo, value: &str) -> bool {
+    match value.parse::<i64>() {
+        Ok(index) if index <= 0 => boot.index == index,
+        _ => boot.id == value.replace('-', "").to_lowercase(),
+    }
+}
+
+/// Shows only the given boot. The time window is dropped, it would mostly hide
+/// the boot that was asked for.
+fn select_boot(boot: &BootInfo) {
+    settings::set_boot(Some(&boot.index.to_string()));
+    settings::set_time_range(None, None);
+}
+
+/// Steps back to the boot before the one shown, or to the previous boot when all
+/// boots are shown, which is where the logs of a crash before a reboot are.
+/// Nothing changes when there is no earlier boot.
+pub fn select_previous_boot(source: &dyn LogSource) {
+    let boots = match source.list_boots(settings::is_user_scope(), settings::get_namespace().as_deref()) {
+        Ok(boots) => boots,
+        Err(e) => {
+            jview_debug::log_debug_info("Failed to list boots: ", format_args!("{}", e));
+            settings::set_message(&format!("Could not list boots: {}", e.describe()), true);
+            return;
+        }
+    };
+    let current = match settings::get_boot() {
+        Some(value) => boots.iter().find(|b| is_boot(b, &value)).map_or(0, |b| b.index),
+        None => 0,
+    };
+
+    if let Some(boot) = boots.iter().find(|b| b.index == current - 1) {
+        select_boot(boot);
+    }
+}
+
+fn format_usec(usec: u64) -> String {
+    Local
+        .timestamp_micros(usec as i64)
+        .single()
+        .map(|time| time.format("%a %Y-%m-%d %H:%M:%S %Z").to_string())
+        .unwrap_or_default()
+}
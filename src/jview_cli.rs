@@ -0,0 +1,183 @@
+use std::io;
+use std::path::PathBuf;
+use std::sync::Arc;
+use clap::Parser;
+use crate::jview_config::settings;
+use crate::jview_error::{self, SourceError};
+use crate::jview_export;
+use crate::jview_filter::SearchMode;
+use crate::jview_journal_file::JournalFileSource;
+use crate::jview_print::OutputFormat;
+use crate::jview_query::{parse_priority, Query, PRIORITY_NAMES};
+use crate::jview_screen::UiScreen;
+use crate::jview_source::{self, LogSource, MemorySource};
+use crate::jview_theme::PriorityTheme;
+use crate::jview_time;
+
+/// Time window of the live journal when no `--since`, `--until` or `--boot` narrows it down.
+const DEFAULT_SINCE: &str = "yesterday";
+
+/// Command line options.
+#[derive(Debug, Parser)]
+#[command(version, about)]
+pub struct Cli {
+    /// Read the journal files in this directory instead of the live journal
+    #[arg(short = 'D', long, value_name = "DIR")]
+    pub directory: Vec<PathBuf>,
+
+    /// Read this journal file instead of the live journal
+    #[arg(long, value_name = "PATH")]
+    pub file: Vec<PathBuf>,
+
+    /// Read a dump made with `journalctl -o export` or `-o json`, `-` for stdin
+    #[arg(short, long, value_name = "PATH", conflicts_with_all = ["directory", "file"])]
+    pub import: Option<PathBuf>,
+
+    /// Show the logs of this unit, repeat to show several units interleaved
+    #[arg(short, long)]
+    pub unit: Vec<String>,
+
+    /// Show the user's own units instead of the system's
+    #[arg(long)]
+    pub user: bool,
+
+    /// Read this journal namespace, "*" for all of them or "+NAME" for it and the default one
+    #[arg(long, value_name = "NAME")]
+    pub namespace: Option<String>,
+
+    /// Show entries on or after this time, e.g. "2024-05-01 13:00", "yesterday" or "1h ago"
+    #[arg(short = 'S', long, value_name = "TIME", value_parser = parse_time_arg)]
+    pub since: Option<String>,
+
+    /// Show entries on or before this time
+    #[arg(short = 'U', long, value_name = "TIME", value_parser = parse_time_arg)]
+    pub until: Option<String>,
+
+    /// Show entries of this priority and more important ones, or a range like "warning..err"
+    #[arg(short, long, value_name = "PRIO", value_parser = parse_priority_arg)]
+    pub priority: Option<(u8, u8)>,
+
+    /// Show entries of one boot: 0 is the current one, -1 the one before, or a boot ID
+    #[arg(short, long, value_name = "ID", num_args = 0..=1, default_missing_value = "0", allow_negative_numbers = true)]
+    pub boot: Option<String>,
+
+    /// Start with this search query in the search box
+    #[arg(short, long, value_parser = parse_query_arg)]
+    pub query: Option<String>,
+
+    /// Start in live follow mode
+    #[arg(short, long, overrides_with = "no_follow")]
+    pub follow: bool,
+
+    /// Start with a static view (the default)
+    #[arg(long, overrides_with = "follow")]
+    pub no_follow: bool,
+
+    /// Colors by priority, e.g. "err=red,warning=yellow:bold,debug=darkgray"
+    #[arg(long, value_name = "SPEC", env = "JOURNALVIEW_COLORS", value_parser = PriorityTheme::parse)]
+    pub colors: Option<PriorityTheme>,
+
+    /// Start with the priority badge column shown
+    #[arg(long)]
+    pub badges: bool,
+
+    /// Disable starting, stopping, restarting and reloading units from the UI
+    #[arg(long)]
+    pub read_only: bool,
+
+    /// Print the matching entries to stdout and exit instead of starting the UI
+    #[arg(long)]
+    pub print: bool,
+
+    /// Output format of --print
+    #[arg(short, long, value_enum, default_value_t = OutputFormat::Text, requires = "print")]
+    pub output: OutputFormat,
+}
+
+fn parse_time_arg(value: &str) -> Result<String, String> {
+    match jview_time::parse_time(value) {
+        Some(_) => Ok(value.to_string()),
+        None => Err("expected a time like \"2024-05-01 13:00\", \"yesterday\" or \"1h ago\"".to_string()),
+    }
+}
+
+fn parse_priority_arg(value: &str) -> Result<(u8, u8), String> {
+    let valid = value.split("..").all(|p| parse_priority(p).is_some());
+    if !valid || value.split("..").count() > 2 {
+        return Err(format!("expected a priority or range of {}", PRIORITY_NAMES.join(", ")));
+    }
+    let (low, high) = jview_source::priority_range(value);
+    Ok((low.min(high), low.max(high)))
+}
+
+fn parse_query_arg(value: &str) -> Result<String, String> {
+    Query::parse(value, SearchMode::Text)
+        .map(|_| value.to_string())
+        .map_err(|e| e.to_string())
+}
+
+impl Cli {
+    /// Opens the journal the options point at, the live one when none is given.
+    ///
+    /// # Returns
+    ///
+    /// The source to browse, or the error from opening the given paths.
+    pub fn open_source(&self) -> jview_error::Result<Arc<dyn LogSource>> {
+        if let Some(path) = &self.import {
+            let entries = jview_export::read_dump(path)
+                .map_err(|e| SourceError::from(io::Error::new(e.kind(), format!("{}: {}", path.display(), e))))?;
+            return Ok(Arc::new(MemorySource::new(entries)));
+        }
+
+        if !self.directory.is_empty() || !self.file.is_empty() {
+            let roots: Vec<PathBuf> = self.directory.iter().chain(&self.file).cloned().collect();
+            if let Some(missing) = roots.iter().find(|root| !root.exists()) {
+                return Err(SourceError::Io(format!("{}: no such file or directory", missing.display())));
+            }
+            return Ok(Arc::new(JournalFileSource::new(roots)));
+        }
+
+        Ok(jview_source::default_source())
+    }
+
+    /// Stores the filters given on the command line where the panes pick them up.
+    ///
+    /// The live journal is kept to a readable size with `DEFAULT_SINCE` unless the
+    /// time window or the boot was given; the time range picker can widen it later.
+    pub fn seed_settings(&self, source: &dyn LogSource) {
+        for unit in &self.unit {
+            settings::add_unit(unit.strip_suffix(".service").unwrap_or(unit));
+        }
+        settings::set_user_scope(self.user);
+        settings::set_namespace(self.namespace.as_deref());
+        settings::set_read_only(self.read_only);
+        let narrowed = self.since.is_some() || self.until.is_some() || self.boot.is_some();
+        let since = if !narrowed && source.is_live() { Some(DEFAULT_SINCE) } else { self.since.as_deref() };
+        settings::set_time_range(since, self.until.as_deref());
+        settings::set_boot(self.boot.as_deref());
+        settings::set_priority(self.priority);
+        if let Some(theme) = self.colors {
+            settings::set_theme(theme);
+        }
+
+        if let Some(text) = &self.query {
+            // Validated while parsing the arguments
+            let query = Query::parse(text, SearchMode::Text).unwrap_or_default();
+            settings::set_search(text, SearchMode::Text);
+            settings::set_matches(query.journal_matches());
+        }
+    }
+
+    /// Applies the startup options that live in the screen state, after `seed_settings`.
+    pub fn seed_screen(&self, screen: &mut UiScreen) {
+        if let Some(query) = &self.query {
+            screen.set_search_query(query);
+        }
+        if self.follow {
+            screen.start_following();
+        }
+        if self.badges {
+            screen.toggle_badges();
+        }
+    }
+}
@@ -1,37 +1,207 @@
 use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::collections::BTreeSet;
 use std::sync::Mutex;
+use std::time::{Duration, Instant};
 
 pub mod settings {
     use super::*;
+    use crate::jview_filter::SearchMode;
+    use crate::jview_theme::PriorityTheme;
 
     // Static variables for various settings
     lazy_static::lazy_static! {
         // Mutex-protected for settings that might require more complex types
-        static ref UNIT: Mutex<String> = Mutex::new(String::new());
+        static ref UNITS: Mutex<BTreeSet<String>> = Mutex::new(BTreeSet::new());
+        static ref SEARCH: Mutex<(String, SearchMode)> = Mutex::new((String::new(), SearchMode::Text));
+        static ref FIND: Mutex<(String, SearchMode)> = Mutex::new((String::new(), SearchMode::Text));
+        static ref MATCHES: Mutex<Vec<String>> = Mutex::new(Vec::new());
+        static ref SINCE: Mutex<Option<String>> = Mutex::new(None);
+        static ref UNTIL: Mutex<Option<String>> = Mutex::new(None);
+        static ref BOOT: Mutex<Option<String>> = Mutex::new(None);
+        static ref NAMESPACE: Mutex<Option<String>> = Mutex::new(None);
+        static ref PRIORITY: Mutex<Option<(u8, u8)>> = Mutex::new(None);
+        static ref THEME: Mutex<PriorityTheme> = Mutex::new(PriorityTheme::default());
+        static ref MESSAGE: Mutex<Option<(String, bool, Instant)>> = Mutex::new(None);
     }
 
+    /// How long a status message stays in the help bar.
+    const MESSAGE_TIMEOUT: Duration = Duration::from_secs(10);
+
     // Atomic values for thread-safe primitive types
     #[allow(dead_code)]
     static MAX_CONNECTIONS: AtomicUsize = AtomicUsize::new(10);
     #[allow(dead_code)]
     static LOGGING_ENABLED: AtomicBool = AtomicBool::new(true);
+    static USER_SCOPE: AtomicBool = AtomicBool::new(false);
+    static READ_ONLY: AtomicBool = AtomicBool::new(false);
 
-    /// Set the username
+    /// Show only this unit
     pub fn set_unit(name: &str) {
-        let mut unit = UNIT.lock().unwrap();
-        *unit = name.to_string();
+        let mut units = UNITS.lock().unwrap();
+        *units = BTreeSet::from([name.to_string()]);
+    }
+
+    /// Add a unit to the ones shown
+    pub fn add_unit(name: &str) {
+        let mut units = UNITS.lock().unwrap();
+        units.insert(name.to_string());
+    }
+
+    /// Add a unit to the ones shown, or take it out if it is already shown
+    pub fn toggle_unit(name: &str) {
+        let mut units = UNITS.lock().unwrap();
+        if !units.remove(name) {
+            units.insert(name.to_string());
+        }
+    }
+
+    /// Get the units shown together, in name order
+    pub fn get_units() -> Vec<String> {
+        let units = UNITS.lock().unwrap();
+        units.iter().cloned().collect()
+    }
+
+    /// Clear the units, to show all of them
+    pub fn clear_units() {
+        let mut units = UNITS.lock().unwrap();
+        units.clear();
+    }
+
+    /// Set the search text used to filter the logs pane
+    pub fn set_search(pattern: &str, mode: SearchMode) {
+        let mut search = SEARCH.lock().unwrap();
+        *search = (pattern.to_string(), mode);
+    }
+
+    /// Get the search text and how it should be interpreted
+    pub fn get_search() -> (String, SearchMode) {
+        let search = SEARCH.lock().unwrap();
+        search.clone()
+    }
+
+    /// Set the pattern the logs pane jumps between matches of, without filtering
+    pub fn set_find(pattern: &str, mode: SearchMode) {
+        let mut find = FIND.lock().unwrap();
+        *find = (pattern.to_string(), mode);
+    }
+
+    /// Get the pattern to jump between matches of
+    pub fn get_find() -> (String, SearchMode) {
+        let find = FIND.lock().unwrap();
+        find.clone()
+    }
+
+    /// Set the journalctl match arguments derived from the submitted search
+    pub fn set_matches(matches: Vec<String>) {
+        let mut current = MATCHES.lock().unwrap();
+        *current = matches;
+    }
+
+    /// Get the journalctl match arguments
+    pub fn get_matches() -> Vec<String> {
+        let matches = MATCHES.lock().unwrap();
+        matches.clone()
+    }
+
+    /// Set the time window of the logs, in journalctl syntax
+    pub fn set_time_range(since: Option<&str>, until: Option<&str>) {
+        *SINCE.lock().unwrap() = since.map(|s| s.to_string());
+        *UNTIL.lock().unwrap() = until.map(|s| s.to_string());
+    }
+
+    /// Get the lower bound of the time window
+    pub fn get_since() -> Option<String> {
+        let since = SINCE.lock().unwrap();
+        since.clone()
+    }
+
+    /// Get the upper bound of the time window
+    pub fn get_until() -> Option<String> {
+        let until = UNTIL.lock().unwrap();
+        until.clone()
+    }
+
+    /// Set the boot to show, as a boot ID or an offset like `-1`
+    pub fn set_boot(boot: Option<&str>) {
+        let mut current = BOOT.lock().unwrap();
+        *current = boot.map(|b| b.to_string());
+    }
+
+    /// Get the boot to show
+    pub fn get_boot() -> Option<String> {
+        let boot = BOOT.lock().unwrap();
+        boot.clone()
+    }
+
+    /// Set the range of priorities to show, most important first
+    pub fn set_priority(range: Option<(u8, u8)>) {
+        let mut priority = PRIORITY.lock().unwrap();
+        *priority = range;
+    }
+
+    /// Get the range of priorities to show
+    pub fn get_priority() -> Option<(u8, u8)> {
+        let priority = PRIORITY.lock().unwrap();
+        *priority
+    }
+
+    /// Set the colors of log lines by priority
+    pub fn set_theme(theme: PriorityTheme) {
+        let mut current = THEME.lock().unwrap();
+        *current = theme;
+    }
+
+    /// Get the colors of log lines by priority
+    pub fn get_theme() -> PriorityTheme {
+        let theme = THEME.lock().unwrap();
+        *theme
+    }
+
+    /// Switch between the system units and the user's own units
+    pub fn set_user_scope(user: bool) {
+        USER_SCOPE.store(user, Ordering::SeqCst);
+    }
+
+    /// Check if the user's own units are shown
+    pub fn is_user_scope() -> bool {
+        USER_SCOPE.load(Ordering::SeqCst)
+    }
+
+    /// Set the journal namespace to read, `None` for the default one
+    pub fn set_namespace(namespace: Option<&str>) {
+        let mut ns = NAMESPACE.lock().unwrap();
+        *ns = namespace.map(|n| n.to_string());
+    }
+
+    /// Get the journal namespace to read
+    pub fn get_namespace() -> Option<String> {
+        let ns = NAMESPACE.lock().unwrap();
+        ns.clone()
+    }
+
+    /// Disable the actions that change the system, like restarting units
+    pub fn set_read_only(read_only: bool) {
+        READ_ONLY.store(read_only, Ordering::SeqCst);
+    }
+
+    /// Check if actions that change the system are disabled
+    pub fn is_read_only() -> bool {
+        READ_ONLY.load(Ordering::SeqCst)
     }
 
-    /// Get the username
-    pub fn get_unit() -> String {
-        let unit = UNIT.lock().unwrap();
-        unit.clone()
+    /// Show a message in the help bar for a while, in red when it is an error
+    pub fn set_message(text: &str, error: bool) {
+        let mut message = MESSAGE.lock().unwrap();
+        *message = Some((text.to_string(), error, Instant::now()));
     }
 
-    /// Clear the unit
-    pub fn clear_unit() {
-        let mut unit = UNIT.lock().unwrap();
-        *unit = String::new(); // Clear the content
+    /// Get the message to show and whether it is an error, until it times out
+    pub fn get_message() -> Option<(String, bool)> {
+        let message = MESSAGE.lock().unwrap();
+        message
+            .as_ref()
+            .filter(|(_, _, at)| at.elapsed() < MESSAGE_TIMEOUT)
+            .map(|(text, error, _)| (text.clone(), *error))
     }
 
     /// Set the maximum number of connections
@@ -18,7 +18,7 @@ impl log::Log for FileLogger {
 
     fn log(&self, record: &Record) {
         if self.enabled(record.metadata()) {
-            if let Some(ref file_mutex) = self.file.as_ref() { // Borrow the file safely
+            if let Some(file_mutex) = self.file.as_ref() { // Borrow the file safely
                 if let Ok(mut file) = file_mutex.lock() {
                     let log_entry = format!(
                         "{} - [{}] {}\n",
@@ -34,7 +34,7 @@ impl log::Log for FileLogger {
     }
 
     fn flush(&self) {
-        if let Some(ref file_mutex) = self.file.as_ref() { // Safely borrow
+        if let Some(file_mutex) = self.file.as_ref() { // Safely borrow
             if let Ok(mut file) = file_mutex.lock() {
                 let _ = file.flush();
             }
@@ -44,7 +44,7 @@ impl log::Log for FileLogger {
 
 impl Drop for FileLogger {
     fn drop(&mut self) {
-        if let Some(ref file_mutex) = self.file.as_ref() {
+        if let Some(file_mutex) = self.file.as_ref() {
             if let Ok(mut file) = file_mutex.lock() {
                 let _ = file.flush();
             }
@@ -55,27 +55,29 @@ impl Drop for FileLogger {
 
 // Lazy initialization of the logger
 static LOGGER: Lazy<FileLogger> = Lazy::new(|| {
+    // Without a writable working directory there is just no debug log
     let file = OpenOptions::new()
         .create(true)
         .append(true)
         .open("./jview.log")
-        .expect("Failed to open log file");
+        .ok();
     FileLogger {
-        file: Some(Mutex::new(BufWriter::new(file))),
+        file: file.map(|file| Mutex::new(BufWriter::new(file))),
     }
 });
 
 /// Initialize the file logger
 pub fn init_debug_log() {
-    log::set_logger(&*LOGGER)
-        .map(|()| log::set_max_level(log::LevelFilter::Info))
-        .expect("Failed to set logger");
+    // Only fails when a logger is already set, which then keeps logging
+    if log::set_logger(&*LOGGER).is_ok() {
+        log::set_max_level(log::LevelFilter::Info);
+    }
 }
 
 /// Shutdown and flush the logs before exiting
 #[allow(dead_code)]
 pub fn shutdown_debug_log() {
-    if let Some(ref file_mutex) = LOGGER.file.as_ref() {
+    if let Some(file_mutex) = LOGGER.file.as_ref() {
         if let Ok(mut file) = file_mutex.lock() {
             let _ = file.flush();
             println!("Shutdown and flushed log data.");
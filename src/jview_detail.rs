@@ -0,0 +1,241 @@
+use std::io::{self, Write};
+use base64::prelude::*;
+use chrono::{Local, TimeZone};
+use ratatui::{
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, List, ListItem},
+};
+use crossterm::event::{KeyCode, KeyEvent};
+use crate::jview_entry::JournalEntry;
+use crate::jview_query::PRIORITY_NAMES;
+use crate::jview_text;
+
+/// Fields listed first, in the order triage needs them: what was logged, by which
+/// process, in which unit and boot, and when.
+const LEADING_FIELDS: [&str; 15] = [
+    "MESSAGE",
+    "PRIORITY",
+    "SYSLOG_IDENTIFIER",
+    "_PID",
+    "_COMM",
+    "_EXE",
+    "_CMDLINE",
+    "_UID",
+    "_SYSTEMD_UNIT",
+    "_SYSTEMD_USER_UNIT",
+    "_HOSTNAME",
+    "_BOOT_ID",
+    "__REALTIME_TIMESTAMP",
+    "__MONOTONIC_TIMESTAMP",
+    "__CURSOR",
+];
+
+/// Widest field name column before names get cut.
+const MAX_NAME_WIDTH: usize = 24;
+
+/// Popup listing every field of one journal entry.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JviewDetail {
+    fields: Vec<(String, String)>, // Field names and raw values, in display order
+    selected_idx: usize,           // Selected field
+    vertical_start: usize,         // First row shown
+    horizontal_start: usize,       // Characters cut from the left of each row
+    max_viewer_height: usize,
+    status: Option<String>, // Result of the last copy
+}
+
+impl JviewDetail {
+    pub fn new(entry: &JournalEntry) -> Self {
+        let mut fields: Vec<(String, String)> = LEADING_FIELDS
+            .iter()
+            .filter_map(|name| entry.get(name).map(|v| (name.to_string(), v.to_string())))
+            .collect();
+        for (name, value) in entry.fields() {
+            if !LEADING_FIELDS.contains(&name.as_str()) {
+                fields.push((name.clone(), value.clone()));
+            }
+        }
+
+        JviewDetail {
+            fields,
+            selected_idx: 0,
+            vertical_start: 0,
+            horizontal_start: 0,
+            max_viewer_height: 25,
+            status: None,
+        }
+    }
+
+    pub fn set_max_height(&mut self, h: usize) {
+        self.max_viewer_height = h;
+    }
+
+    /// Each field takes one row per line of its value.
+    fn rows(&self) -> Vec<(usize, Line<'static>)> {
+        let width = self
+            .fields
+            .iter()
+            .map(|(name, _)| name.len())
+            .max()
+            .unwrap_or(0)
+            .min(MAX_NAME_WIDTH);
+        let mut rows = Vec::new();
+
+        for (i, (name, value)) in self.fields.iter().enumerate() {
+            let shown = describe(name, value);
+            for (n, line) in shown.split('\n').enumerate() {
+                let label = if n == 0 { name.as_str() } else { "" };
+                let label = format!("{:<width$}  ", label, width = width);
+                let label_len = label.chars().count();
+
+                // Scroll the label away first, then the value
+                let label: String = label.chars().skip(self.horizontal_start).collect();
+                let value = line[jview_text::skip_columns(line, self.horizontal_start.saturating_sub(label_len))..].to_string();
+                rows.push((
+                    i,
+                    Line::from(vec![
+                        Span::styled(label, Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD)),
+                        Span::raw(value),
+                    ]),
+                ));
+            }
+        }
+        rows
+    }
+
+    /// Number of rows that fit inside the borders of the popup.
+    fn visible_rows(&self) -> usize {
+        self.max_viewer_height.saturating_sub(2)
+    }
+
+    /// Scrolls so that every row of the selected field is on screen, if it fits.
+    fn scroll_to_selected(&mut self) {
+        let rows = self.rows();
+        let first = rows.iter().position(|(i, _)| *i == self.selected_idx).unwrap_or(0);
+        let last = rows.iter().rposition(|(i, _)| *i == self.selected_idx).unwrap_or(0);
+
+        if last >= self.vertical_start + self.visible_rows() {
+            self.vertical_start = (last + 1).saturating_sub(self.visible_rows());
+        }
+        if first < self.vertical_start {
+            self.vertical_start = first;
+        }
+    }
+
+    /// Handles a key while the popup is open.
+    ///
+    /// # Returns
+    ///
+    /// `false` once the popup should be closed.
+    pub fn navigate(&mut self, key: KeyEvent) -> bool {
+        let page = self.visible_rows().max(1);
+        self.status = None;
+
+        match key.code {
+            KeyCode::Esc | KeyCode::Enter | KeyCode::Char('q') => return false,
+            KeyCode::Up if self.selected_idx > 0 => self.selected_idx -= 1,
+            KeyCode::Down if self.selected_idx + 1 < self.fields.len() => self.selected_idx += 1,
+            KeyCode::PageUp => self.selected_idx = self.selected_idx.saturating_sub(page),
+            KeyCode::PageDown => self.selected_idx = (self.selected_idx + page).min(self.fields.len().saturating_sub(1)),
+            KeyCode::Home => self.selected_idx = 0,
+            KeyCode::End => self.selected_idx = self.fields.len().saturating_sub(1),
+            KeyCode::Left if self.horizontal_start > 0 => self.horizontal_start -= 1,
+            KeyCode::Right => self.horizontal_start += 1,
+            KeyCode::Char('y') => {
+                if let Some((name, value)) = self.fields.get(self.selected_idx) {
+                    self.status = Some(copy_status(copy_to_clipboard(value), name));
+                }
+            }
+            KeyCode::Char('Y') => {
+                let text: Vec<String> = self.fields.iter().map(|(name, value)| format!("{}={}", name, value)).collect();
+                self.status = Some(copy_status(copy_to_clipboard(&text.join("\n")), "entry"));
+            }
+            _ => {}
+        }
+
+        self.scroll_to_selected();
+        true
+    }
+
+    /// Creates the popup widget.
+    ///
+    /// # Returns
+    ///
+    /// A `List` widget with one row per line of every field value.
+    pub fn get_detail_widget(&self) -> List<'static> {
+        let selected = Style::default().fg(Color::Black).bg(Color::Cyan);
+        let items: Vec<ListItem> = self
+            .rows()
+            .into_iter()
+            .skip(self.vertical_start)
+            .take(self.visible_rows())
+            .map(|(i, line)| {
+                if i == self.selected_idx {
+                    ListItem::new(line).style(selected)
+                } else {
+                    ListItem::new(line)
+                }
+            })
+            .collect();
+
+        let mut title = vec![Span::raw(format!(
+            "Entry: {} fields [y]: Copy value | [Y]: Copy entry | [Esc]: Close",
+            self.fields.len()
+        ))];
+        if let Some(status) = &self.status {
+            title.push(Span::styled(format!(" {} ", status), Style::default().fg(Color::Black).bg(Color::Green)));
+        }
+
+        List::new(items)
+            .block(Block::default().borders(Borders::ALL).title(Line::from(title)))
+            .style(Style::default().fg(Color::White).bg(Color::Black))
+    }
+}
+
+/// Adds a readable form to values that are hard to read raw.
+fn describe(name: &str, value: &str) -> String {
+    match name {
+        "PRIORITY" => match value.parse::<usize>().ok().and_then(|p| PRIORITY_NAMES.get(p)) {
+            Some(priority) => format!("{} ({})", value, priority),
+            None => value.to_string(),
+        },
+        "__REALTIME_TIMESTAMP" => match value.parse().ok().and_then(|usec| Local.timestamp_micros(usec).single()) {
+            Some(time) => format!("{} ({})", value, time.format("%Y-%m-%d %H:%M:%S%.6f %Z")),
+            None => value.to_string(),
+        },
+        "__MONOTONIC_TIMESTAMP" => match value.parse::<u64>() {
+            Ok(usec) => format!("{} ({}.{:06}s after boot)", value, usec / 1_000_000, usec % 1_000_000),
+            Err(_) => value.to_string(),
+        },
+        _ => value.to_string(),
+    }
+}
+
+/// Puts text on the clipboard of the terminal with an OSC 52 escape sequence,
+/// which also works over SSH.
+fn copy_to_clipboard(text: &str) -> io::Result<()> {
+    let mut stdout = io::stdout();
+    write!(stdout, "\x1b]52;c;{}\x07", BASE64_STANDARD.encode(text))?;
+    stdout.flush()
+}
+
+fn copy_status(result: io::Result<()>, what: &str) -> String {
+    match result {
+        Ok(()) => format!("Copied {}", what),
+        Err(e) => format!("Copy failed: {}", e),
+    }
+}
+
+/// Centers a popup of the given percentage size inside `area`.
+pub fn popup_area(area: Rect, percent_x: u16, percent_y: u16) -> Rect {
+    let width = area.width * percent_x / 100;
+    let height = area.height * percent_y / 100;
+    Rect {
+        x: area.x + (area.width - width) / 2,
+        y: area.y + (area.height - height) / 2,
+        width,
+        height,
+    }
+}
@@ -0,0 +1,182 @@
+use std::collections::BTreeMap;
+use chrono::{Local, TimeZone};
+use serde_json::Value;
+
+/// A single journal record together with every field journald stored for it.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct JournalEntry {
+    fields: BTreeMap<String, String>,
+}
+
+impl JournalEntry {
+    pub fn new() -> Self {
+        JournalEntry {
+            fields: BTreeMap::new(),
+        }
+    }
+
+    /// Parses one line of `journalctl -o json` output.
+    ///
+    /// # Arguments
+    ///
+    /// * `line` - A single JSON object as printed by journalctl.
+    ///
+    /// # Returns
+    ///
+    /// The parsed entry, or `None` if the line is not a JSON object.
+    pub fn from_json(line: &str) -> Option<Self> {
+        let value: Value = serde_json::from_str(line).ok()?;
+        JournalEntry::from_json_value(&value)
+    }
+
+    /// Builds an entry from a JSON object in the journalctl JSON format.
+    pub fn from_json_value(value: &Value) -> Option<Self> {
+        let object = value.as_object()?;
+        let mut entry = JournalEntry::new();
+
+        for (name, value) in object {
+            if let Some(text) = json_field_value(value) {
+                entry.set(name, &text);
+            }
+        }
+
+        Some(entry)
+    }
+
+    pub fn set(&mut self, name: &str, value: &str) {
+        self.fields.insert(name.to_string(), value.to_string());
+    }
+
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.fields.get(name).map(|v| v.as_str())
+    }
+
+    /// All fields of the entry, sorted by field name.
+    pub fn fields(&self) -> impl Iterator<Item = (&String, &String)> {
+        self.fields.iter()
+    }
+
+    pub fn message(&self) -> &str {
+        self.get("MESSAGE").unwrap_or("")
+    }
+
+    /// Syslog priority, 0 (emerg) to 7 (debug).
+    pub fn priority(&self) -> Option<u8> {
+        self.get("PRIORITY")?.parse().ok()
+    }
+
+    pub fn pid(&self) -> Option<&str> {
+        self.get("_PID").or_else(|| self.get("SYSLOG_PID"))
+    }
+
+    pub fn unit(&self) -> Option<&str> {
+        self.get("_SYSTEMD_UNIT").or_else(|| self.get("_SYSTEMD_USER_UNIT"))
+    }
+
+    pub fn hostname(&self) -> Option<&str> {
+        self.get("_HOSTNAME")
+    }
+
+    pub fn cursor(&self) -> Option<&str> {
+        self.get("__CURSOR")
+    }
+
+    /// Name of the logging process, the way journalctl prints it in front of the message.
+    pub fn identifier(&self) -> &str {
+        self.get("SYSLOG_IDENTIFIER")
+            .or_else(|| self.get("_COMM"))
+            .unwrap_or("unknown")
+    }
+
+    /// Wallclock timestamp in microseconds since the epoch.
+    pub fn realtime_usec(&self) -> Option<u64> {
+        self.get("__REALTIME_TIMESTAMP")?.parse().ok()
+    }
+
+    /// Formats the realtime timestamp in local time like `journalctl -o short`.
+    pub fn timestamp(&self) -> String {
+        self.realtime_usec()
+            .and_then(|usec| Local.timestamp_micros(usec as i64).single())
+            .map(|t| t.format("%b %d %H:%M:%S").to_string())
+            .unwrap_or_default()
+    }
+
+    /// Formats the realtime timestamp in local time like `journalctl -o short-iso-precise`.
+    pub fn iso_timestamp(&self) -> String {
+        self.realtime_usec()
+            .and_then(|usec| Local.timestamp_micros(usec as i64).single())
+            .map(|t| t.format("%Y-%m-%dT%H:%M:%S%.6f%:z").to_string())
+            .unwrap_or_default()
+    }
+
+    /// Renders the entry as one line of `journalctl -o json` output.
+    pub fn to_json(&self) -> String {
+        let object: serde_json::Map<String, Value> = self
+            .fields
+            .iter()
+            .map(|(name, value)| (name.clone(), Value::String(value.clone())))
+            .collect();
+        Value::Object(object).to_string()
+    }
+
+    /// Renders the entry as a single line in the same layout as `journalctl -o short`.
+    pub fn to_line(&self) -> String {
+        let pid = match self.pid() {
+            Some(pid) => format!("[{}]", pid),
+            None => String::new(),
+        };
+
+        format!(
+            "{} {} {}{}: {}",
+            self.timestamp(),
+            self.hostname().unwrap_or("localhost"),
+            self.identifier(),
+            pid,
+            self.message()
+        )
+    }
+}
+
+/// Converts a JSON field value to text.
+///
+/// journalctl prints plain fields as strings, binary fields as arrays of bytes,
+/// fields with several values as arrays, and oversized fields as null.
+fn json_field_value(value: &Value) -> Option<String> {
+    match value {
+        Value::String(s) => Some(s.clone()),
+        Value::Number(n) => Some(n.to_string()),
+        Value::Array(items) if items.iter().all(|i| i.is_u64()) => {
+            let bytes: Vec<u8> = items.iter().filter_map(|i| i.as_u64()).map(|b| b as u8).collect();
+            Some(String::from_utf8_lossy(&bytes).into_owned())
+        }
+        Value::Array(items) => {
+            let values: Vec<String> = items.iter().filter_map(json_field_value).collect();
+            Some(values.join("\n"))
+        }
+        _ => None,
+    }
+}
+
+/// Entries to test with, shared by the tests of every module that reads or
+/// filters entries.
+#[cfg(test)]
+pub mod fixtures {
+    use super::JournalEntry;
+
+    /// An entry with exactly the given fields.
+    pub fn entry(fields: &[(&str, &str)]) -> JournalEntry {
+        let mut entry = JournalEntry::new();
+        for (name, value) in fields {
+            entry.set(name, value);
+        }
+        entry
+    }
+
+    /// Entry `n` of a fixture journal: cursor `c<n>`, logged `n` seconds into it.
+    pub fn numbered(n: u64, fields: &[(&str, &str)]) -> JournalEntry {
+        let mut entry = entry(fields);
+        entry.set("__CURSOR", &format!("c{}", n));
+        entry.set("__REALTIME_TIMESTAMP", &((1_700_000_000 + n) * 1_000_000).to_string());
+        entry
+    }
+}
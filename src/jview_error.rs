@@ -0,0 +1,156 @@
+use std::error::Error;
+use std::fmt;
+use std::io;
+use ratatui::{
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Paragraph, Wrap},
+};
+
+/// Why a source could not read the journal or reach the system manager.
+///
+/// Every variant knows enough to tell the user what went wrong and, for the
+/// common setup problems, what to do about it; see `hint`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SourceError {
+    /// A program the source runs is not installed
+    MissingTool(String),
+    /// Reading the journal or managing units was refused
+    PermissionDenied { what: String, message: String },
+    /// A program ran and failed, with the first line of its error output
+    CommandFailed { program: String, message: String },
+    /// The source can't do this, e.g. manage the units of a journal read from files
+    Unsupported(String),
+    /// Reading or parsing failed
+    Io(String),
+    /// The query can't be run, e.g. a time bound that is not a valid timestamp
+    InvalidQuery(String),
+}
+
+pub type Result<T> = std::result::Result<T, SourceError>;
+
+/// What journalctl and systemctl print when they are not allowed to do something.
+const PERMISSION_MESSAGES: [&str; 5] = [
+    "access denied",
+    "permission denied",
+    "insufficient permissions",
+    "interactive authentication required",
+    "not authorized",
+];
+
+impl SourceError {
+    /// The error of starting a program.
+    pub fn from_spawn(program: &str, e: io::Error) -> Self {
+        match e.kind() {
+            io::ErrorKind::NotFound => SourceError::MissingTool(program.to_string()),
+            io::ErrorKind::PermissionDenied => SourceError::PermissionDenied {
+                what: program.to_string(),
+                message: e.to_string(),
+            },
+            _ => SourceError::Io(format!("{}: {}", program, e)),
+        }
+    }
+
+    /// The error of a program that ran and failed, from what it printed on stderr.
+    pub fn from_stderr(program: &str, stderr: &str) -> Self {
+        let message = stderr
+            .lines()
+            .map(str::trim)
+            .find(|line| !line.is_empty())
+            .unwrap_or("exited with an error")
+            .to_string();
+        let lower = stderr.to_lowercase();
+
+        if PERMISSION_MESSAGES.iter().any(|m| lower.contains(m)) {
+            SourceError::PermissionDenied {
+                what: program.to_string(),
+                message,
+            }
+        } else {
+            SourceError::CommandFailed {
+                program: program.to_string(),
+                message,
+            }
+        }
+    }
+
+    /// What the user can do about the error, when it is a known setup problem.
+    pub fn hint(&self) -> Option<String> {
+        match self {
+            SourceError::MissingTool(tool) => Some(format!("Install {} or add its directory to PATH", tool)),
+            SourceError::PermissionDenied { what, .. } if what == "systemctl" => {
+                Some("Run journalview as root or with sudo, or allow it through polkit".to_string())
+            }
+            SourceError::PermissionDenied { .. } => Some(
+                "Add yourself to the systemd-journal group (sudo usermod -aG systemd-journal $USER) and log in again, or run with sudo"
+                    .to_string(),
+            ),
+            _ => None,
+        }
+    }
+
+    /// The error and its hint in one line, for the help bar and the terminal.
+    pub fn describe(&self) -> String {
+        match self.hint() {
+            Some(hint) => format!("{}. {}", self.to_string().trim_end_matches('.'), hint),
+            None => self.to_string(),
+        }
+    }
+}
+
+impl fmt::Display for SourceError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SourceError::MissingTool(tool) => write!(f, "{} is not installed", tool),
+            SourceError::PermissionDenied { what, message } => write!(f, "{}: {}", what, message),
+            SourceError::CommandFailed { program, message } => write!(f, "{}: {}", program, message),
+            SourceError::Unsupported(message) | SourceError::Io(message) | SourceError::InvalidQuery(message) => {
+                write!(f, "{}", message)
+            }
+        }
+    }
+}
+
+impl Error for SourceError {}
+
+impl From<io::Error> for SourceError {
+    fn from(e: io::Error) -> Self {
+        // A source error that went through an io::Error on the way, e.g. in --print
+        if let Some(inner) = e.get_ref().and_then(|inner| inner.downcast_ref::<SourceError>()) {
+            return inner.clone();
+        }
+        match e.kind() {
+            io::ErrorKind::PermissionDenied => SourceError::PermissionDenied {
+                what: "journal files".to_string(),
+                message: e.to_string(),
+            },
+            io::ErrorKind::Unsupported => SourceError::Unsupported(e.to_string()),
+            _ => SourceError::Io(e.to_string()),
+        }
+    }
+}
+
+/// Creates the dialog shown when the logs could not be read.
+///
+/// # Arguments
+///
+/// * `error` - What went wrong.
+///
+/// # Returns
+///
+/// A `Paragraph` widget with the error, and the hint when there is one.
+pub fn get_error_widget(error: &SourceError) -> Paragraph<'static> {
+    let mut lines = vec![Line::from(Span::styled(
+        error.to_string(),
+        Style::default().fg(Color::LightRed).add_modifier(Modifier::BOLD),
+    ))];
+    if let Some(hint) = error.hint() {
+        lines.push(Line::from(""));
+        lines.push(Line::from(format!("Hint: {}", hint)));
+    }
+
+    Paragraph::new(lines)
+        .wrap(Wrap { trim: true })
+        .block(Block::default().borders(Borders::ALL).title("Could not read the logs [r]: Retry | [Esc]: Dismiss"))
+        .style(Style::default().fg(Color::White).bg(Color::Black))
+}
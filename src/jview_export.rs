@@ -0,0 +1,177 @@
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, Read};
+use std::path::Path;
+use serde_json::{Deserializer, Value};
+use crate::jview_entry::JournalEntry;
+
+/// Reads a journal dump saved with `journalctl -o export` or `-o json`.
+///
+/// The format is detected from the first byte: JSON dumps start with `{` or `[`
+/// (one object per line, pretty printed objects or a single array), anything
+/// else is read as the export format.
+///
+/// # Arguments
+///
+/// * `path` - The dump file, or `-` to read it from standard input.
+///
+/// # Returns
+///
+/// The entries in the order they appear in the dump.
+pub fn read_dump(path: &Path) -> io::Result<Vec<JournalEntry>> {
+    let reader: Box<dyn Read> = if path == Path::new("-") {
+        Box::new(io::stdin())
+    } else {
+        Box::new(File::open(path)?)
+    };
+    let mut reader = BufReader::new(reader);
+
+    let first = reader
+        .fill_buf()?
+        .iter()
+        .find(|b| !b.is_ascii_whitespace())
+        .copied();
+    match first {
+        Some(b'{') | Some(b'[') => parse_json(reader),
+        _ => parse_export(reader),
+    }
+}
+
+/// Parses concatenated JSON objects or arrays of objects.
+fn parse_json(reader: impl Read) -> io::Result<Vec<JournalEntry>> {
+    let mut entries = Vec::new();
+
+    for value in Deserializer::from_reader(reader).into_iter::<Value>() {
+        match value? {
+            Value::Array(items) => entries.extend(items.iter().filter_map(JournalEntry::from_json_value)),
+            value => entries.extend(JournalEntry::from_json_value(&value)),
+        }
+    }
+    Ok(entries)
+}
+
+/// Parses the journal export format: `FIELD=value` lines, entries separated by
+/// an empty line, and binary fields written as the field name, a little endian
+/// 64-bit length and the raw data.
+fn parse_export(mut reader: impl BufRead) -> io::Result<Vec<JournalEntry>> {
+    let mut entries = Vec::new();
+    let mut entry = JournalEntry::new();
+    let mut line = Vec::new();
+
+    loop {
+        line.clear();
+        if reader.read_until(b'\n', &mut line)? == 0 {
+            break;
+        }
+        if line.last() == Some(&b'\n') {
+            line.pop();
+        }
+
+        if line.is_empty() {
+            if entry != JournalEntry::new() {
+                entries.push(std::mem::take(&mut entry));
+            }
+            continue;
+        }
+
+        match line.iter().position(|&b| b == b'=') {
+            Some(eq) => {
+                let name = String::from_utf8_lossy(&line[..eq]).into_owned();
+                add_value(&mut entry, &name, &line[eq + 1..]);
+            }
+            None => {
+                let name = String::from_utf8_lossy(&line).into_owned();
+                let mut size = [0u8; 8];
+                reader.read_exact(&mut size)?;
+                // Only take as much as arrived, a damaged dump may claim any length
+                let len = u64::from_le_bytes(size);
+                let mut data = Vec::new();
+                (&mut reader).take(len).read_to_end(&mut data)?;
+                if data.len() as u64 != len {
+                    return Err(io::Error::new(
+                        io::ErrorKind::InvalidData,
+                        format!("field {} is {} bytes long, but the dump ends after {}", name, len, data.len()),
+                    ));
+                }
+                reader.read_exact(&mut [0u8; 1])?; // Trailing newline
+                add_value(&mut entry, &name, &data);
+            }
+        }
+    }
+
+    if entry != JournalEntry::new() {
+        entries.push(entry);
+    }
+    Ok(entries)
+}
+
+/// Sets a field, joining repeated fields with newlines like the JSON reader does.
+fn add_value(entry: &mut JournalEntry, name: &str, data: &[u8]) {
+    let value = String::from_utf8_lossy(data);
+    match entry.get(name) {
+        Some(existing) => {
+            let joined = format!("{}\n{}", existing, value);
+            entry.set(name, &joined);
+        }
+        None => entry.set(name, &value),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn export(bytes: &[u8]) -> io::Result<Vec<JournalEntry>> {
+        parse_export(bytes)
+    }
+
+    #[test]
+    fn export_entries_are_separated_by_empty_lines() {
+        let entries = export(b"__CURSOR=a\nMESSAGE=one=1\n\n\n__CURSOR=b\nMESSAGE=two\n").unwrap();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].message(), "one=1");
+        assert_eq!(entries[1].cursor(), Some("b"));
+    }
+
+    #[test]
+    fn export_reads_binary_fields() {
+        let mut dump = b"__CURSOR=a\nMESSAGE\n".to_vec();
+        dump.extend_from_slice(&11u64.to_le_bytes());
+        dump.extend_from_slice(b"two\nlines\n!\n");
+        dump.extend_from_slice(b"PRIORITY=3\n\n");
+
+        let entries = export(&dump).unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].message(), "two\nlines\n!");
+        assert_eq!(entries[0].priority(), Some(3));
+    }
+
+    #[test]
+    fn export_joins_repeated_fields() {
+        let entries = export(b"TAG=a\nTAG=b\n").unwrap();
+        assert_eq!(entries[0].get("TAG"), Some("a\nb"));
+    }
+
+    #[test]
+    fn export_rejects_lengths_past_the_end() {
+        let mut dump = b"__CURSOR=a\nMESSAGE\n".to_vec();
+        dump.extend_from_slice(&u64::MAX.to_le_bytes());
+        dump.extend_from_slice(b"hi\n\n");
+
+        let error = export(&dump).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn export_rejects_a_truncated_length() {
+        assert!(export(b"MESSAGE\n\x05\x00").is_err());
+    }
+
+    #[test]
+    fn json_reads_lines_and_arrays() {
+        let lines = parse_json(&b"{\"MESSAGE\":\"a\"}\n{\"MESSAGE\":[104,105]}\n"[..]).unwrap();
+        assert_eq!(lines.iter().map(|e| e.message()).collect::<Vec<_>>(), ["a", "hi"]);
+
+        let array = parse_json(&b"[{\"MESSAGE\":\"a\"}, {\"MESSAGE\":\"b\"}]"[..]).unwrap();
+        assert_eq!(array.len(), 2);
+    }
+}
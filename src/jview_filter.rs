@@ -0,0 +1,80 @@
+use regex::{Regex, RegexBuilder};
+
+/// How the text typed into the search box is interpreted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SearchMode {
+    Text,  // Plain substring
+    Regex, // Regular expression
+}
+
+impl SearchMode {
+    pub fn toggle(&self) -> SearchMode {
+        match self {
+            SearchMode::Text => SearchMode::Regex,
+            SearchMode::Regex => SearchMode::Text,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            SearchMode::Text => "text",
+            SearchMode::Regex => "regex",
+        }
+    }
+
+    /// The mode with the given `name()`.
+    pub fn from_name(name: &str) -> Option<SearchMode> {
+        match name {
+            "text" => Some(SearchMode::Text),
+            "regex" => Some(SearchMode::Regex),
+            _ => None,
+        }
+    }
+}
+
+/// A compiled search pattern used to filter and highlight log lines.
+///
+/// Both modes use smart case: matching ignores case unless the pattern
+/// contains an uppercase letter.
+#[derive(Debug, Clone)]
+pub struct LogFilter {
+    pattern: Regex,
+}
+
+impl LogFilter {
+    /// Compiles a search pattern.
+    ///
+    /// # Arguments
+    ///
+    /// * `pattern` - The text typed by the user.
+    /// * `mode` - Whether `pattern` is a plain substring or a regular expression.
+    ///
+    /// # Returns
+    ///
+    /// The filter, or the regex error when `pattern` is not a valid expression.
+    pub fn new(pattern: &str, mode: SearchMode) -> Result<Self, regex::Error> {
+        let source = match mode {
+            SearchMode::Text => regex::escape(pattern),
+            SearchMode::Regex => pattern.to_string(),
+        };
+        let ignore_case = !pattern.chars().any(|c| c.is_uppercase());
+
+        let pattern = RegexBuilder::new(&source)
+            .case_insensitive(ignore_case)
+            .build()?;
+        Ok(LogFilter { pattern })
+    }
+
+    pub fn matches(&self, text: &str) -> bool {
+        self.pattern.is_match(text)
+    }
+
+    /// Byte ranges of every non-empty match in `text`, in order.
+    pub fn find_ranges(&self, text: &str) -> Vec<(usize, usize)> {
+        self.pattern
+            .find_iter(text)
+            .filter(|m| !m.is_empty())
+            .map(|m| (m.start(), m.end()))
+            .collect()
+    }
+}
@@ -0,0 +1,122 @@
+use std::io::{BufRead, BufReader, Read};
+use std::process::{Child, Command, Stdio};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver};
+use std::sync::Arc;
+use std::thread;
+use crate::jview_debug;
+use crate::jview_entry::JournalEntry;
+use crate::jview_error::{self, SourceError};
+
+/// Live feed of new journal entries produced on a background thread and handed
+/// to the UI through a channel. Dropping the follower stops the producer.
+///
+/// A producer that can't go on sends why as its last message.
+#[derive(Debug)]
+pub struct LogFollower {
+    receiver: Receiver<jview_error::Result<JournalEntry>>,
+    child: Option<Child>,       // journalctl process, when following through journalctl
+    stop: Arc<AtomicBool>,      // Tells polling threads to exit
+    error: Option<SourceError>, // Why the producer stopped, until taken
+}
+
+impl LogFollower {
+    /// Wraps a channel fed by a thread that watches `stop`.
+    pub fn new(receiver: Receiver<jview_error::Result<JournalEntry>>, stop: Arc<AtomicBool>) -> Self {
+        LogFollower {
+            receiver,
+            child: None,
+            stop,
+            error: None,
+        }
+    }
+
+    /// Starts a long-running `journalctl -f -o json` and parses its output.
+    ///
+    /// # Arguments
+    ///
+    /// * `filter_args` - journalctl arguments selecting the entries to follow.
+    /// * `after_cursor` - Resume right after this cursor so no entry is missed or repeated.
+    ///
+    /// # Returns
+    ///
+    /// The running follower, or the error from spawning journalctl.
+    pub fn journalctl(filter_args: &[String], after_cursor: Option<&str>) -> jview_error::Result<Self> {
+        let mut jargs = vec!["-f".to_string(), "-o".to_string(), "json".to_string()];
+
+        match after_cursor {
+            Some(cursor) => jargs.push(format!("--after-cursor={}", cursor)),
+            None => jargs.push("--lines=0".to_string()),
+        }
+        jargs.extend_from_slice(filter_args);
+
+        jview_debug::log_debug_info("Following with command:", format_args!("{:?}", jargs));
+        let mut child = Command::new("journalctl")
+            .args(&jargs)
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|e| SourceError::from_spawn("journalctl", e))?;
+
+        let stdout = child.stdout.take().expect("journalctl stdout is piped");
+        let mut stderr = child.stderr.take().expect("journalctl stderr is piped");
+        let (sender, receiver) = mpsc::channel();
+        let stop = Arc::new(AtomicBool::new(false));
+
+        // Collected on its own thread, so warnings can't fill the pipe and block journalctl
+        let errors = thread::spawn(move || {
+            let mut message = String::new();
+            let _ = stderr.read_to_string(&mut message);
+            message
+        });
+        let stopped = stop.clone();
+        thread::spawn(move || {
+            for line in BufReader::new(stdout).lines() {
+                let Ok(line) = line else { break };
+                if let Some(entry) = JournalEntry::from_json(&line) {
+                    if sender.send(Ok(entry)).is_err() {
+                        return; // Follower was dropped
+                    }
+                }
+            }
+            // journalctl -f only ends by itself when it fails, e.g. on a refused permission
+            let message = errors.join().unwrap_or_default();
+            if !stopped.load(Ordering::SeqCst) {
+                let _ = sender.send(Err(SourceError::from_stderr("journalctl", &message)));
+            }
+        });
+
+        let mut follower = LogFollower::new(receiver, stop);
+        follower.child = Some(child);
+        Ok(follower)
+    }
+
+    /// Returns every entry the reader thread has received since the last call.
+    /// When the producer failed, the error is kept for `take_error`.
+    pub fn drain(&mut self) -> Vec<JournalEntry> {
+        let mut entries = Vec::new();
+        for message in self.receiver.try_iter() {
+            match message {
+                Ok(entry) => entries.push(entry),
+                Err(e) => self.error = Some(e),
+            }
+        }
+        entries
+    }
+
+    /// Why the producer stopped, once it did. No more entries arrive after it.
+    pub fn take_error(&mut self) -> Option<SourceError> {
+        self.error.take()
+    }
+}
+
+impl Drop for LogFollower {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::SeqCst);
+        if let Some(child) = self.child.as_mut() {
+            let _ = child.kill();
+            let _ = child.wait();
+        }
+    }
+}
@@ -1,8 +1,11 @@
 use ratatui::{
-    style::{Style, Color},
+    style::{Style, Color, Modifier},
+    text::Span,
     widgets::{Block, Borders, Paragraph},
 };
 use ratatui::style;
+use crate::jview_config::settings;
+use crate::jview_screen::UiSection;
 
 fn get_style() -> style::Style {
     Style::default().fg(Color::Green)
@@ -12,12 +15,26 @@ fn get_style() -> style::Style {
 /// 
 /// # Arguments
 /// 
+/// * `section` - The section the keys apply to.
 ///
 /// # Returns
 /// 
-/// A `Paragraph` widget configured for the search functionality.
-pub fn get_widget() -> Paragraph<'static> {
-    Paragraph::new("Help [Tab]: Switch Sections | [Up/Down/Left/Right]: Scroll | [q]: Quit")
-        .block(Block::default().borders(Borders::ALL))
+/// A `Paragraph` widget with the keys of the section, and the latest status
+/// message in its border.
+pub fn get_widget(section: UiSection) -> Paragraph<'static> {
+    let text = match section {
+        UiSection::Selector => "Help [Tab]: Switch Sections | [Enter]: Show | [Space]: Add | [a]: All | [/]: Filter | [v]: Type | [o]: Order | [u/n]: User/Namespace | [s/x/r/l]: Start/Stop/Restart/Reload | [q]: Quit",
+        _ => "Help [Tab]: Switch Sections | [Up/Down/PgUp/PgDn/Home/End]: Scroll | [t/T]: Go to time/Time range | [b/B]: Boots/Previous boot | [/ n N]: Find | [Enter]: Details | [p]: Priority | [w]: Wrap | [f]: Follow | [Space]: Pause | [u]: User | [q]: Quit",
+    };
+
+    let mut block = Block::default().borders(Borders::ALL);
+    if let Some((message, error)) = settings::get_message() {
+        let color = if error { Color::Red } else { Color::Blue };
+        let style = Style::default().fg(Color::White).bg(color).add_modifier(Modifier::BOLD);
+        block = block.title(Span::styled(format!(" {} ", message), style));
+    }
+
+    Paragraph::new(text)
+        .block(block)
         .style(get_style())
-}
\ No newline at end of file
+}
@@ -0,0 +1,179 @@
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use crate::jview_debug;
+use crate::jview_filter::SearchMode;
+
+/// Most submitted queries kept in the history file.
+const MAX_HISTORY: usize = 500;
+
+/// Queries submitted in the search box, oldest first, kept across runs in
+/// `$XDG_STATE_HOME/journalview/history`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SearchHistory {
+    entries: Vec<(String, SearchMode)>,
+    path: Option<PathBuf>, // None when there is no home directory to keep it in
+}
+
+impl SearchHistory {
+    pub fn load() -> Self {
+        let path = user_file("XDG_STATE_HOME", ".local/state", "history");
+        let entries = path
+            .as_ref()
+            .map(|p| read_records(p, 2))
+            .unwrap_or_default()
+            .into_iter()
+            .filter_map(|mut fields| {
+                let query = fields.pop()?;
+                Some((query, SearchMode::from_name(&fields[0])?))
+            })
+            .collect();
+
+        SearchHistory { entries, path }
+    }
+
+    pub fn entries(&self) -> &[(String, SearchMode)] {
+        &self.entries
+    }
+
+    /// Adds a submitted query as the newest entry and saves the history.
+    ///
+    /// A query that was already in the history moves to the end instead of being
+    /// kept twice.
+    pub fn push(&mut self, query: &str, mode: SearchMode) {
+        if query.trim().is_empty() {
+            return;
+        }
+        self.entries.retain(|(q, m)| !(q == query && *m == mode));
+        self.entries.push((query.to_string(), mode));
+        if self.entries.len() > MAX_HISTORY {
+            self.entries.drain(..self.entries.len() - MAX_HISTORY);
+        }
+
+        let records: Vec<Vec<&str>> = self.entries.iter().map(|(q, m)| vec![m.name(), q.as_str()]).collect();
+        save_records(self.path.as_deref(), &records);
+    }
+
+    /// Newest entry older than `before` that contains `needle`, for Ctrl-R.
+    ///
+    /// # Arguments
+    ///
+    /// * `needle` - Text typed after Ctrl-R.
+    /// * `before` - Index to search back from, `entries().len()` to start at the newest.
+    ///
+    /// # Returns
+    ///
+    /// The index of the matching entry.
+    pub fn search(&self, needle: &str, before: usize) -> Option<usize> {
+        self.entries[..before.min(self.entries.len())]
+            .iter()
+            .rposition(|(query, _)| query.contains(needle))
+    }
+}
+
+/// A query saved under a name to recall it from the picker.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SavedQuery {
+    pub name: String,
+    pub query: String,
+    pub mode: SearchMode,
+}
+
+/// Named queries, kept in `$XDG_CONFIG_HOME/journalview/queries` so they can
+/// also be edited by hand and shared.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SavedQueries {
+    queries: Vec<SavedQuery>,
+    path: Option<PathBuf>,
+}
+
+impl SavedQueries {
+    pub fn load() -> Self {
+        let path = user_file("XDG_CONFIG_HOME", ".config", "queries");
+        let queries = path
+            .as_ref()
+            .map(|p| read_records(p, 3))
+            .unwrap_or_default()
+            .into_iter()
+            .filter_map(|fields| {
+                Some(SavedQuery {
+                    mode: SearchMode::from_name(&fields[1])?,
+                    name: fields[0].clone(),
+                    query: fields[2].clone(),
+                })
+            })
+            .collect();
+
+        SavedQueries { queries, path }
+    }
+
+    pub fn list(&self) -> &[SavedQuery] {
+        &self.queries
+    }
+
+    /// Saves a query under `name`, replacing any query saved under it before.
+    pub fn save(&mut self, name: &str, query: &str, mode: SearchMode) {
+        let saved = SavedQuery {
+            name: name.to_string(),
+            query: query.to_string(),
+            mode,
+        };
+        match self.queries.iter_mut().find(|q| q.name == name) {
+            Some(existing) => *existing = saved,
+            None => self.queries.push(saved),
+        }
+        self.write();
+    }
+
+    pub fn remove(&mut self, idx: usize) {
+        if idx < self.queries.len() {
+            self.queries.remove(idx);
+            self.write();
+        }
+    }
+
+    fn write(&self) {
+        let records: Vec<Vec<&str>> = self
+            .queries
+            .iter()
+            .map(|q| vec![q.name.as_str(), q.mode.name(), q.query.as_str()])
+            .collect();
+        save_records(self.path.as_deref(), &records);
+    }
+}
+
+/// Path of a journalview file under an XDG base directory, falling back to the
+/// usual place in the home directory.
+fn user_file(xdg_var: &str, home_default: &str, name: &str) -> Option<PathBuf> {
+    let base = env::var_os(xdg_var)
+        .map(PathBuf::from)
+        .filter(|p| p.is_absolute())
+        .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(home_default)))?;
+    Some(base.join("journalview").join(name))
+}
+
+/// Reads a file of tab separated records, the last field of which may itself
+/// contain tabs. Lines with too few fields are skipped.
+fn read_records(path: &Path, fields: usize) -> Vec<Vec<String>> {
+    let Ok(text) = fs::read_to_string(path) else {
+        return Vec::new(); // Nothing saved yet
+    };
+    text.lines()
+        .map(|line| line.splitn(fields, '\t').map(str::to_string).collect::<Vec<_>>())
+        .filter(|record| record.len() == fields)
+        .collect()
+}
+
+/// Writes tab separated records, logging rather than failing: losing history
+/// must not get in the way of reading logs.
+fn save_records(path: Option<&Path>, records: &[Vec<&str>]) {
+    let Some(path) = path else { return };
+    let text: String = records.iter().map(|r| r.join("\t") + "\n").collect();
+    let result = path
+        .parent()
+        .map_or(Ok(()), fs::create_dir_all)
+        .and_then(|_| fs::write(path, text));
+    if let Err(e) = result {
+        jview_debug::log_debug_info("Failed to save ", format_args!("{}: {}", path.display(), e));
+    }
+}
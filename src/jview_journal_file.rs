@@ -0,0 +1,1000 @@
+use std::cmp;
+use std::collections::hash_map::Entry;
+use std::collections::{HashMap, HashSet};
+use std::fs::{self, File};
+use std::io::{self, Read, Write};
+use std::os::unix::fs::MetadataExt;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::thread;
+use std::time::Duration;
+use memmap2::Mmap;
+use crate::jview_debug;
+use crate::jview_entry::JournalEntry;
+use crate::jview_error;
+use crate::jview_follow::LogFollower;
+use crate::jview_source::{self, LogQuery, LogSource, UnitInfo};
+
+// Native reader for the systemd journal file format, see
+// https://systemd.io/JOURNAL_FILE_FORMAT/ for the layout of the structures below.
+// All integers are little endian.
+
+const SIGNATURE: &[u8; 8] = b"LPKSHHRH";
+
+// Header incompatible flags
+const HEADER_INCOMPATIBLE_COMPRESSED_XZ: u32 = 1 << 0;
+const HEADER_INCOMPATIBLE_COMPRESSED_LZ4: u32 = 1 << 1;
+const HEADER_INCOMPATIBLE_KEYED_HASH: u32 = 1 << 2;
+const HEADER_INCOMPATIBLE_COMPRESSED_ZSTD: u32 = 1 << 3;
+const HEADER_INCOMPATIBLE_COMPACT: u32 = 1 << 4;
+const HEADER_INCOMPATIBLE_SUPPORTED: u32 = HEADER_INCOMPATIBLE_COMPRESSED_XZ
+    | HEADER_INCOMPATIBLE_COMPRESSED_LZ4
+    | HEADER_INCOMPATIBLE_KEYED_HASH
+    | HEADER_INCOMPATIBLE_COMPRESSED_ZSTD
+    | HEADER_INCOMPATIBLE_COMPACT;
+
+// Object types
+const OBJECT_DATA: u8 = 1;
+const OBJECT_FIELD: u8 = 2;
+const OBJECT_ENTRY: u8 = 3;
+const OBJECT_ENTRY_ARRAY: u8 = 6;
+
+// Object flags
+const OBJECT_COMPRESSED_XZ: u8 = 1 << 0;
+const OBJECT_COMPRESSED_LZ4: u8 = 1 << 1;
+const OBJECT_COMPRESSED_ZSTD: u8 = 1 << 2;
+
+const OBJECT_HEADER_SIZE: u64 = 16;
+
+/// Largest payload journald writes for one field, as `DATA_SIZE_MAX` in journald.
+/// Sizes stored in a file are not trusted beyond it.
+const DATA_SIZE_MAX: u64 = 768 * 1024 * 1024;
+
+/// Where journald keeps persistent and volatile journals.
+pub const DEFAULT_DIRECTORIES: [&str; 2] = ["/var/log/journal", "/run/log/journal"];
+
+/// How often a follower checks the journal files for new entries.
+const FOLLOW_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+fn invalid(message: String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// Collects decompressed data, failing once it grows past `DATA_SIZE_MAX`, so a
+/// damaged stream can't fill the memory.
+struct BoundedOutput(Vec<u8>);
+
+impl Write for BoundedOutput {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if (self.0.len() + buf.len()) as u64 > DATA_SIZE_MAX {
+            return Err(invalid("implausible size".to_string()));
+        }
+        self.0.extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct ObjectHeader {
+    kind: u8,
+    flags: u8,
+    size: u64,
+}
+
+/// Fixed part of an entry object, as needed to build the journal cursor.
+#[derive(Debug, Clone, Copy)]
+struct EntryHeader {
+    seqnum: u64,
+    realtime: u64,
+    monotonic: u64,
+    boot_id: [u8; 16],
+    xor_hash: u64,
+}
+
+/// A single `.journal` file, mapped into memory so only the parts looked at are
+/// read. It remembers how far its entries were read, to only decode new ones
+/// while journald appends to it.
+#[derive(Debug)]
+pub struct JournalFile {
+    path: PathBuf,
+    file: File,
+    data: Mmap,
+    incompatible_flags: u32,
+    seqnum_id: [u8; 16],
+    next_array: u64, // Entry array with the first entry not read yet, 0 before the first array
+    next_item: u64,  // Index of that entry in the array
+    n_read: u64,     // Entries read so far
+}
+
+impl JournalFile {
+    /// Reads and validates a journal file.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - Path of a `.journal` or `.journal~` file.
+    ///
+    /// # Returns
+    ///
+    /// The opened file, or an error if it can't be read or isn't a journal.
+    pub fn open(path: &Path) -> io::Result<Self> {
+        let file = File::open(path)?;
+        // SAFETY: journald only ever appends to a journal file, the mapped part
+        // does not shrink while it is read
+        let data = unsafe { Mmap::map(&file)? };
+        let mut file = JournalFile {
+            path: path.to_path_buf(),
+            file,
+            data,
+            incompatible_flags: 0,
+            seqnum_id: [0; 16],
+            next_array: 0,
+            next_item: 0,
+            n_read: 0,
+        };
+
+        if file.data.len() < 208 || &file.data[0..8] != SIGNATURE {
+            return Err(invalid(format!("{}: not a journal file", path.display())));
+        }
+
+        file.incompatible_flags = file.u32_at(12)?;
+        if file.incompatible_flags & !HEADER_INCOMPATIBLE_SUPPORTED != 0 {
+            return Err(invalid(format!(
+                "{}: unsupported journal features {:#x}",
+                path.display(),
+                file.incompatible_flags
+            )));
+        }
+
+        let seqnum_id: [u8; 16] = file.bytes_at(72, 16)?.try_into().unwrap();
+        file.seqnum_id = seqnum_id;
+        Ok(file)
+    }
+
+    /// Maps the file again when journald has made it larger since it was mapped.
+    fn remap(&mut self) -> io::Result<()> {
+        let len = self.file.metadata()?.len();
+        if len < self.data.len() as u64 {
+            return Err(invalid(format!("{}: file was truncated", self.path.display())));
+        }
+        if len > self.data.len() as u64 {
+            // SAFETY: see `open`
+            self.data = unsafe { Mmap::map(&self.file)? };
+        }
+        Ok(())
+    }
+
+    fn is_compact(&self) -> bool {
+        self.incompatible_flags & HEADER_INCOMPATIBLE_COMPACT != 0
+    }
+
+    fn bytes_at(&self, offset: u64, len: u64) -> io::Result<&[u8]> {
+        let start = usize::try_from(offset).map_err(|_| invalid("offset out of range".to_string()))?;
+        let end = start
+            .checked_add(len as usize)
+            .filter(|end| *end <= self.data.len())
+            .ok_or_else(|| invalid(format!("{}: truncated at offset {}", self.path.display(), offset)))?;
+        Ok(&self.data[start..end])
+    }
+
+    fn u32_at(&self, offset: u64) -> io::Result<u32> {
+        let bytes = self.bytes_at(offset, 4)?;
+        Ok(u32::from_le_bytes(bytes.try_into().unwrap()))
+    }
+
+    fn u64_at(&self, offset: u64) -> io::Result<u64> {
+        let bytes = self.bytes_at(offset, 8)?;
+        Ok(u64::from_le_bytes(bytes.try_into().unwrap()))
+    }
+
+    fn object_header(&self, offset: u64, expected: u8) -> io::Result<ObjectHeader> {
+        let header = ObjectHeader {
+            kind: self.bytes_at(offset, 1)?[0],
+            flags: self.bytes_at(offset + 1, 1)?[0],
+            size: self.u64_at(offset + 8)?,
+        };
+
+        if header.kind != expected || header.size < OBJECT_HEADER_SIZE {
+            return Err(invalid(format!(
+                "{}: expected object type {} at offset {}, found {}",
+                self.path.display(),
+                expected,
+                offset,
+                header.kind
+            )));
+        }
+        Ok(header)
+    }
+
+    /// Offsets of the entry objects written since the last call, in the order they
+    /// were written. Stops at the first damaged array, keeping the entries before it.
+    fn new_entry_offsets(&mut self) -> io::Result<Vec<u64>> {
+        // The header of a damaged file may claim any number of entries
+        let n_entries = self.u64_at(152)?;
+        let item_size = if self.is_compact() { 4 } else { 8 };
+        let capacity = n_entries.saturating_sub(self.n_read).min(self.data.len() as u64 / item_size);
+        let mut offsets = Vec::with_capacity(capacity as usize);
+
+        match self.walk_entry_arrays(n_entries, &mut offsets) {
+            Err(e) if offsets.is_empty() => Err(e),
+            _ => Ok(offsets),
+        }
+    }
+
+    /// Follows the chain of entry arrays from where the last walk stopped, keeping
+    /// the position after every entry found.
+    fn walk_entry_arrays(&mut self, n_entries: u64, offsets: &mut Vec<u64>) -> io::Result<()> {
+        let item_size = if self.is_compact() { 4 } else { 8 };
+        let mut visited = HashSet::new();
+        if self.next_array == 0 {
+            self.next_array = self.u64_at(176)?;
+        }
+
+        // A damaged chain of arrays may loop, keep the entries up to there
+        while self.next_array != 0 && self.n_read < n_entries && visited.insert(self.next_array) {
+            let array = self.next_array;
+            let header = self.object_header(array, OBJECT_ENTRY_ARRAY)?;
+            let n_items = header.size.saturating_sub(24) / item_size;
+
+            while self.next_item < n_items && self.n_read < n_entries {
+                let item = array + 24 + self.next_item * item_size;
+                let offset = if self.is_compact() {
+                    self.u32_at(item)? as u64
+                } else {
+                    self.u64_at(item)?
+                };
+                if offset == 0 {
+                    return Ok(()); // Unused tail of the last array, filled in later
+                }
+                offsets.push(offset);
+                self.next_item += 1;
+                self.n_read += 1;
+            }
+            if self.next_item < n_items {
+                return Ok(());
+            }
+
+            let next = self.u64_at(array + 16)?;
+            if next == 0 {
+                return Ok(()); // Last array, the next one is linked once it is full
+            }
+            self.next_array = next;
+            self.next_item = 0;
+        }
+
+        Ok(())
+    }
+
+    /// Decompressed `FIELD=value` payload of a data object.
+    fn data_payload(&self, offset: u64) -> io::Result<Vec<u8>> {
+        let header = self.object_header(offset, OBJECT_DATA)?;
+        let payload_offset = if self.is_compact() { 72 } else { 64 };
+        if header.size < payload_offset {
+            return Err(invalid(format!("{}: short data object at {}", self.path.display(), offset)));
+        }
+        let payload = self.bytes_at(offset + payload_offset, header.size - payload_offset)?;
+
+        if header.flags & OBJECT_COMPRESSED_XZ != 0 {
+            let mut out = BoundedOutput(Vec::new());
+            lzma_rs::xz_decompress(&mut &payload[..], &mut out)
+                .map_err(|e| invalid(format!("xz: {} at offset {}", e, offset)))?;
+            Ok(out.0)
+        } else if header.flags & OBJECT_COMPRESSED_LZ4 != 0 {
+            // The uncompressed size is stored in front of the LZ4 block. An LZ4 block
+            // grows by at most 255 times, so a larger size is damage
+            if payload.len() < 8 {
+                return Err(invalid("lz4: short payload".to_string()));
+            }
+            let size = u64::from_le_bytes(payload[..8].try_into().unwrap());
+            let limit = ((payload.len() as u64 - 8) * 255).min(DATA_SIZE_MAX);
+            if size > limit {
+                return Err(invalid(format!("lz4: implausible size {} at offset {}", size, offset)));
+            }
+            lz4_flex::block::decompress(&payload[8..], size as usize).map_err(|e| invalid(format!("lz4: {}", e)))
+        } else if header.flags & OBJECT_COMPRESSED_ZSTD != 0 {
+            let mut out = Vec::new();
+            let mut source = payload;
+            let mut decoder = ruzstd::decoding::StreamingDecoder::new(&mut source)
+                .map_err(|e| invalid(format!("zstd: {}", e)))?;
+            // A frame may claim any size, stop reading once it is implausible
+            (&mut decoder).take(DATA_SIZE_MAX + 1).read_to_end(&mut out)?;
+            if out.len() as u64 > DATA_SIZE_MAX {
+                return Err(invalid(format!("zstd: implausible size at offset {}", offset)));
+            }
+            Ok(out)
+        } else {
+            Ok(payload.to_vec())
+        }
+    }
+
+    fn entry_header(&self, offset: u64) -> io::Result<(ObjectHeader, EntryHeader)> {
+        let header = self.object_header(offset, OBJECT_ENTRY)?;
+        let mut boot_id = [0; 16];
+        boot_id.copy_from_slice(self.bytes_at(offset + 40, 16)?);
+
+        let entry = EntryHeader {
+            seqnum: self.u64_at(offset + 16)?,
+            realtime: self.u64_at(offset + 24)?,
+            monotonic: self.u64_at(offset + 32)?,
+            boot_id,
+            xor_hash: self.u64_at(offset + 56)?,
+        };
+        Ok((header, entry))
+    }
+
+    /// Builds the entry at `offset` with all its data fields plus the same
+    /// address fields (`__CURSOR`, timestamps, boot id) that journalctl adds.
+    fn read_entry(&self, offset: u64) -> io::Result<JournalEntry> {
+        let (header, fixed) = self.entry_header(offset)?;
+        let item_size = if self.is_compact() { 4 } else { 16 };
+        let n_items = (header.size.saturating_sub(64)) / item_size;
+        let mut entry = JournalEntry::new();
+
+        for i in 0..n_items {
+            let item = offset + 64 + i * item_size;
+            let data_offset = if self.is_compact() {
+                self.u32_at(item)? as u64
+            } else {
+                self.u64_at(item)?
+            };
+
+            let payload = self.data_payload(data_offset)?;
+            let Some(eq) = payload.iter().position(|b| *b == b'=') else {
+                continue; // Not a FIELD=value pair
+            };
+            let name = String::from_utf8_lossy(&payload[..eq]);
+            let value = String::from_utf8_lossy(&payload[eq + 1..]);
+            match entry.get(&name) {
+                Some(existing) => {
+                    let joined = format!("{}\n{}", existing, value);
+                    entry.set(&name, &joined);
+                }
+                None => entry.set(&name, &value),
+            }
+        }
+
+        let cursor = format!(
+            "s={};i={:x};b={};m={:x};t={:x};x={:x}",
+            hex(&self.seqnum_id),
+            fixed.seqnum,
+            hex(&fixed.boot_id),
+            fixed.monotonic,
+            fixed.realtime,
+            fixed.xor_hash
+        );
+        entry.set("__CURSOR", &cursor);
+        entry.set("__REALTIME_TIMESTAMP", &fixed.realtime.to_string());
+        entry.set("__MONOTONIC_TIMESTAMP", &fixed.monotonic.to_string());
+        entry.set("__SEQNUM", &fixed.seqnum.to_string());
+        entry.set("_BOOT_ID", &hex(&fixed.boot_id));
+        Ok(entry)
+    }
+
+    /// The entries added to the file since the last call, all of them on the first
+    /// call, oldest first. Entries that can't be decoded are skipped.
+    pub fn new_entries(&mut self) -> io::Result<Vec<JournalEntry>> {
+        self.remap()?;
+        let entries = self
+            .new_entry_offsets()?
+            .into_iter()
+            .filter_map(|offset| self.read_entry(offset).ok())
+            .collect();
+        Ok(entries)
+    }
+
+    /// Distinct values of a field across the whole file, like `journalctl -F`.
+    ///
+    /// Walks the field hash table to the field object and then the chain of data
+    /// objects linked from it, so no entry has to be read.
+    pub fn field_values(&self, field: &str) -> io::Result<Vec<String>> {
+        let table_offset = self.u64_at(120)?;
+        let table_size = self.u64_at(128)?;
+        let mut values = Vec::new();
+        // Chains of a damaged file may loop, every object is visited once at most
+        let mut visited = HashSet::new();
+
+        // Each hash table item holds the head and tail offsets of one hash chain
+        for bucket in 0..table_size / 16 {
+            let mut offset = self.u64_at(table_offset + bucket * 16)?;
+            while offset != 0 && visited.insert(offset) {
+                let header = self.object_header(offset, OBJECT_FIELD)?;
+                let name = self.bytes_at(offset + 40, header.size.saturating_sub(40))?;
+
+                if name == field.as_bytes() {
+                    let mut data = self.u64_at(offset + 32)?;
+                    while data != 0 && visited.insert(data) {
+                        let payload = self.data_payload(data)?;
+                        if let Some(value) = payload.get(field.len() + 1..) {
+                            values.push(String::from_utf8_lossy(value).into_owned());
+                        }
+                        data = self.u64_at(data + 32)?;
+                    }
+                    return Ok(values);
+                }
+                offset = self.u64_at(offset + 24)?;
+            }
+        }
+
+        Ok(values)
+    }
+}
+
+/// Every journal file below `dir`, including the per-machine subdirectories
+/// journald creates and files it renamed to `.journal~` after an unclean shutdown.
+pub fn journal_files(dir: &Path) -> io::Result<Vec<PathBuf>> {
+    let mut files = Vec::new();
+
+    for item in fs::read_dir(dir)? {
+        let path = item?.path();
+        if path.is_dir() {
+            files.extend(journal_files(&path)?);
+        } else if let Some(name) = path.file_name().and_then(|n| n.to_str()) {
+            if name.ends_with(".journal") || name.ends_with(".journal~") {
+                files.push(path);
+            }
+        }
+    }
+
+    files.sort();
+    Ok(files)
+}
+
+/// Orders entries of several journal files like journalctl merges them: by
+/// sequence number when both were written by the same journald instance (same
+/// `_SEQNUM_ID`), by wallclock time otherwise, as the clock may have been changed.
+/// Equal sequence numbers, left behind by a journald that was restarted from an
+/// old state, fall back to the wallclock time as well.
+fn entry_order(a: &JournalEntry, b: &JournalEntry) -> cmp::Ordering {
+    let seqnum = |e: &JournalEntry| e.get("__SEQNUM").and_then(|s| s.parse::<u64>().ok());
+    if seqnum_id(a).is_some() && seqnum_id(a) == seqnum_id(b) {
+        if let (Some(x), Some(y)) = (seqnum(a), seqnum(b)) {
+            if x != y {
+                return x.cmp(&y);
+            }
+        }
+    }
+    a.realtime_usec().unwrap_or(0).cmp(&b.realtime_usec().unwrap_or(0))
+}
+
+fn seqnum_id(entry: &JournalEntry) -> Option<&str> {
+    entry.cursor()?.strip_prefix("s=")?.split(';').next()
+}
+
+/// Merges two runs of entries that are each in journal order. Unlike sorting,
+/// merging copes with `entry_order` not being transitive across sequence number IDs.
+fn merge_entries(mut older: Vec<JournalEntry>, newer: Vec<JournalEntry>) -> Vec<JournalEntry> {
+    match (older.last(), newer.first()) {
+        (Some(last), Some(first)) if entry_order(last, first).is_gt() => {}
+        _ => {
+            older.extend(newer); // Already in order, the usual case while following
+            return older;
+        }
+    }
+
+    let mut merged = Vec::with_capacity(older.len() + newer.len());
+    let mut older = older.into_iter().peekable();
+    let mut newer = newer.into_iter().peekable();
+    while let (Some(a), Some(b)) = (older.peek(), newer.peek()) {
+        let next = if entry_order(b, a).is_lt() { newer.next() } else { older.next() };
+        merged.extend(next);
+    }
+    merged.extend(older);
+    merged.extend(newer);
+    merged
+}
+
+/// The same entry shows up in several files once journald has flushed the runtime
+/// journal to disk; like journalctl, treat entries with the same boot, timestamps
+/// and hash as one.
+fn entry_location(entry: &JournalEntry) -> Option<String> {
+    entry.cursor().and_then(|c| c.split_once(";b=")).map(|(_, rest)| rest.to_string())
+}
+
+/// The journal files read so far and the newest `MAX_ENTRIES` entries read from
+/// them, merged in journal order.
+#[derive(Debug, Default)]
+struct JournalCache {
+    files: HashMap<(u64, u64), JournalFile>, // By device and inode, so a file rotated by journald keeps its position
+    entries: Vec<JournalEntry>,              // Oldest first
+    seen: HashSet<Option<String>>,           // Locations of `entries`, see `entry_location`
+}
+
+impl JournalCache {
+    /// Reads the entries added to the given files since the last update.
+    ///
+    /// Files that can't be opened are skipped as long as at least one could be
+    /// read, the way journalctl ignores corrupted files.
+    fn update(&mut self, paths: &[PathBuf]) -> io::Result<()> {
+        let mut new = Vec::new();
+        let mut present = HashSet::new();
+        let mut last_error = None;
+        let mut any_read = false;
+
+        for path in paths {
+            let key = match fs::metadata(path) {
+                Ok(meta) => (meta.dev(), meta.ino()),
+                Err(e) => {
+                    last_error = Some(e);
+                    continue;
+                }
+            };
+            present.insert(key);
+
+            let file = match self.files.entry(key) {
+                Entry::Occupied(file) => file.into_mut(),
+                Entry::Vacant(slot) => match JournalFile::open(path) {
+                    Ok(file) => slot.insert(file),
+                    Err(e) => {
+                        last_error = Some(e);
+                        continue;
+                    }
+                },
+            };
+            match file.new_entries() {
+                Ok(entries) => {
+                    any_read = true;
+                    new = merge_entries(new, entries);
+                }
+                Err(e) => {
+                    // Read it from the start again next time, e.g. after it was truncated
+                    self.files.remove(&key);
+                    last_error = Some(e);
+                }
+            }
+        }
+        // Files journald deleted; their entries stay
+        self.files.retain(|key, _| present.contains(key));
+
+        if !any_read {
+            if let Some(e) = last_error {
+                return Err(e);
+            }
+        }
+
+        new.retain(|e| self.seen.insert(entry_location(e)));
+        self.entries = merge_entries(std::mem::take(&mut self.entries), new);
+
+        // Keep the newest entries like the logs pane does, and forget the others
+        let excess = self.entries.len().saturating_sub(jview_source::MAX_ENTRIES);
+        for entry in self.entries.drain(..excess) {
+            self.seen.remove(&entry_location(&entry));
+        }
+        Ok(())
+    }
+}
+
+/// Distinct values of a field across all given journal files, sorted.
+pub fn read_field_values(paths: &[PathBuf], field: &str) -> Vec<String> {
+    let mut values: Vec<String> = paths
+        .iter()
+        .filter_map(|p| JournalFile::open(p).and_then(|f| f.field_values(field)).ok())
+        .flatten()
+        .collect();
+    values.sort();
+    values.dedup();
+    values
+}
+
+/// Reads journal files natively, for hosts without the systemd tools.
+#[derive(Debug)]
+pub struct JournalFileSource {
+    roots: Vec<PathBuf>,             // Journal files, or directories searched for them
+    live: bool,                      // Roots are where journald of this machine writes
+    cache: Arc<Mutex<JournalCache>>, // Entries read so far, shared with the followers
+}
+
+impl JournalFileSource {
+    pub fn new(roots: Vec<PathBuf>) -> Self {
+        JournalFileSource {
+            roots,
+            live: false,
+            cache: Arc::new(Mutex::new(JournalCache::default())),
+        }
+    }
+
+    /// The directories journald writes to on this machine.
+    pub fn system() -> Self {
+        JournalFileSource {
+            live: true,
+            ..JournalFileSource::new(DEFAULT_DIRECTORIES.iter().map(PathBuf::from).collect())
+        }
+    }
+
+    /// The cache, updated with the entries written since the last read.
+    fn read(&self) -> jview_error::Result<MutexGuard<'_, JournalCache>> {
+        let files = self.files();
+        jview_debug::log_debug_info("Reading journal files:", format_args!("{:?}", files));
+        let mut cache = self.cache.lock().unwrap();
+        cache.update(&files)?;
+        Ok(cache)
+    }
+
+    /// Expands the roots into journal files, looked up again on every read so
+    /// rotated files are picked up.
+    fn files(&self) -> Vec<PathBuf> {
+        self.roots
+            .iter()
+            .flat_map(|root| {
+                if root.is_dir() {
+                    journal_files(root).unwrap_or_default()
+                } else {
+                    vec![root.clone()]
+                }
+            })
+            .collect()
+    }
+}
+
+impl LogSource for JournalFileSource {
+    fn list_units(&self, user: bool) -> jview_error::Result<Vec<UnitInfo>> {
+        let field = if user { "_SYSTEMD_USER_UNIT" } else { "_SYSTEMD_UNIT" };
+        Ok(jview_source::named_units(read_field_values(&self.files(), field)))
+    }
+
+    fn query(&self, query: &LogQuery) -> jview_error::Result<Vec<JournalEntry>> {
+        query.select(&self.read()?.entries)
+    }
+
+    fn follow(&self, query: &LogQuery) -> jview_error::Result<LogFollower> {
+        // Start from the newest entry the query selects, the cursor has to be one of them
+        let mut cursor = match &query.after_cursor {
+            Some(cursor) => Some(cursor.clone()),
+            None => self.query(query)?.last().and_then(|e| e.cursor()).map(|c| c.to_string()),
+        };
+        let mut query = query.clone();
+        let source = JournalFileSource {
+            roots: self.roots.clone(),
+            live: self.live,
+            cache: self.cache.clone(),
+        };
+        let stop = Arc::new(AtomicBool::new(false));
+        let (sender, receiver) = mpsc::channel();
+
+        // Resolve relative time bounds and boot offsets once, like journalctl -f
+        let matcher = query.matcher(&self.read()?.entries)?;
+
+        let stopped = stop.clone();
+        thread::spawn(move || {
+            while !stopped.load(Ordering::SeqCst) {
+                query.after_cursor = cursor.clone();
+                // Without a cursor the journal was empty, so everything is new
+                let entries = match source.read() {
+                    Ok(cache) => query.select_with(&matcher, &cache.entries),
+                    Err(e) => {
+                        let _ = sender.send(Err(e));
+                        return;
+                    }
+                };
+                for entry in entries {
+                    cursor = entry.cursor().map(|c| c.to_string());
+                    if sender.send(Ok(entry)).is_err() {
+                        return; // Follower was dropped
+                    }
+                }
+                thread::sleep(FOLLOW_POLL_INTERVAL);
+            }
+        });
+
+        Ok(LogFollower::new(receiver, stop))
+    }
+
+    fn is_live(&self) -> bool {
+        self.live
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::process;
+
+    /// Writes small journal files, laid out the way journald writes them.
+    struct Fixture {
+        bytes: Vec<u8>,
+        compact: bool,
+    }
+
+    impl Fixture {
+        fn new(incompatible_flags: u32, seqnum_id: u8) -> Self {
+            let mut fixture = Fixture {
+                bytes: vec![0; 272],
+                compact: incompatible_flags & HEADER_INCOMPATIBLE_COMPACT != 0,
+            };
+            fixture.bytes[0..8].copy_from_slice(SIGNATURE);
+            fixture.bytes[12..16].copy_from_slice(&incompatible_flags.to_le_bytes());
+            fixture.bytes[72..88].copy_from_slice(&[seqnum_id; 16]);
+            fixture.put_u64(88, 272);
+            fixture
+        }
+
+        fn put_u64(&mut self, at: u64, value: u64) {
+            self.bytes[at as usize..at as usize + 8].copy_from_slice(&value.to_le_bytes());
+        }
+
+        /// Appends an object and returns its offset.
+        fn object(&mut self, kind: u8, flags: u8, body: &[u8]) -> u64 {
+            self.bytes.resize(self.bytes.len().next_multiple_of(8), 0);
+            let offset = self.bytes.len() as u64;
+            self.bytes.extend_from_slice(&[kind, flags, 0, 0, 0, 0, 0, 0]);
+            self.bytes.extend_from_slice(&(OBJECT_HEADER_SIZE + body.len() as u64).to_le_bytes());
+            self.bytes.extend_from_slice(body);
+            offset
+        }
+
+        fn data(&mut self, payload: &str, compression: u8) -> u64 {
+            let payload = payload.as_bytes();
+            let mut body = vec![0; if self.compact { 56 } else { 48 }];
+            match compression {
+                OBJECT_COMPRESSED_XZ => lzma_rs::xz_compress(&mut &payload[..], &mut body).unwrap(),
+                OBJECT_COMPRESSED_LZ4 => {
+                    body.extend_from_slice(&(payload.len() as u64).to_le_bytes());
+                    body.extend(lz4_flex::block::compress(payload));
+                }
+                OBJECT_COMPRESSED_ZSTD => {
+                    body.extend(ruzstd::encoding::compress_to_vec(payload, ruzstd::encoding::CompressionLevel::Fastest))
+                }
+                _ => body.extend_from_slice(payload),
+            }
+            self.object(OBJECT_DATA, compression, &body)
+        }
+
+        /// An entry with a `MESSAGE` field, logged `seqnum` seconds into the boot.
+        fn entry(&mut self, seqnum: u64, realtime: u64, message: &str, compression: u8) -> u64 {
+            let data = self.data(&format!("MESSAGE={}", message), compression);
+            let mut body = Vec::new();
+            for value in [seqnum, realtime * 1_000_000, seqnum * 1_000_000] {
+                body.extend_from_slice(&value.to_le_bytes());
+            }
+            body.extend_from_slice(&[1; 16]); // Boot ID
+            body.extend_from_slice(&seqnum.to_le_bytes()); // Hash of the entry
+            self.item(&mut body, data);
+            if !self.compact {
+                body.extend_from_slice(&0u64.to_le_bytes()); // Hash of the data
+            }
+            self.object(OBJECT_ENTRY, 0, &body)
+        }
+
+        /// An entry array with room for `capacity` entries, the unused ones zero.
+        fn array(&mut self, entries: &[u64], capacity: usize) -> u64 {
+            let mut body = vec![0; 8];
+            for i in 0..capacity {
+                self.item(&mut body, entries.get(i).copied().unwrap_or(0));
+            }
+            self.object(OBJECT_ENTRY_ARRAY, 0, &body)
+        }
+
+        fn item(&self, body: &mut Vec<u8>, offset: u64) {
+            if self.compact {
+                body.extend_from_slice(&(offset as u32).to_le_bytes());
+            } else {
+                body.extend_from_slice(&offset.to_le_bytes());
+            }
+        }
+
+        fn set_array_item(&mut self, array: u64, index: u64, entry: u64) {
+            let item = array + 24 + index * if self.compact { 4 } else { 8 };
+            let bytes = if self.compact { (entry as u32).to_le_bytes().to_vec() } else { entry.to_le_bytes().to_vec() };
+            self.bytes[item as usize..item as usize + bytes.len()].copy_from_slice(&bytes);
+        }
+
+        fn link(&mut self, array: u64, next: u64) {
+            self.put_u64(array + 16, next);
+        }
+
+        fn first_array(&self) -> u64 {
+            u64::from_le_bytes(self.bytes[176..184].try_into().unwrap())
+        }
+
+        fn set_entries(&mut self, first_array: u64, n_entries: u64) {
+            self.put_u64(176, first_array);
+            self.put_u64(152, n_entries);
+        }
+
+        /// Entries with the given messages, logged a second apart, in one array.
+        fn with_messages(incompatible_flags: u32, messages: &[&str]) -> Self {
+            let mut fixture = Fixture::new(incompatible_flags, 7);
+            let entries: Vec<u64> =
+                (1..).zip(messages).map(|(n, m)| fixture.entry(n, 1_700_000_000 + n, m, 0)).collect();
+            let array = fixture.array(&entries, entries.len());
+            fixture.set_entries(array, entries.len() as u64);
+            fixture
+        }
+
+        /// Writes the file over the previous version in place, like journald appends.
+        fn write(&self, name: &str) -> PathBuf {
+            let path = std::env::temp_dir().join(format!("jview-{}-{}.journal", process::id(), name));
+            let mut file = fs::OpenOptions::new().create(true).truncate(false).write(true).open(&path).unwrap();
+            file.write_all(&self.bytes).unwrap();
+            path
+        }
+    }
+
+    fn messages(entries: &[JournalEntry]) -> Vec<&str> {
+        entries.iter().map(|e| e.message()).collect()
+    }
+
+    fn read(fixture: &Fixture, name: &str) -> io::Result<Vec<JournalEntry>> {
+        let path = fixture.write(name);
+        let entries = JournalFile::open(&path).and_then(|mut f| f.new_entries());
+        fs::remove_file(path).unwrap();
+        entries
+    }
+
+    #[test]
+    fn open_rejects_what_is_not_a_journal() {
+        let mut fixture = Fixture::with_messages(0, &["one"]);
+        fixture.bytes.truncate(200);
+        assert!(read(&fixture, "short").is_err());
+
+        let mut fixture = Fixture::with_messages(0, &["one"]);
+        fixture.bytes[0] = b'X';
+        assert!(read(&fixture, "signature").is_err());
+
+        let fixture = Fixture::with_messages(1 << 5, &["one"]);
+        let error = read(&fixture, "features").unwrap_err();
+        assert!(error.to_string().contains("unsupported journal features 0x20"));
+    }
+
+    #[test]
+    fn regular_and_compact_arrays_read_the_same() {
+        for flags in [0, HEADER_INCOMPATIBLE_COMPACT] {
+            let entries = read(&Fixture::with_messages(flags, &["one", "two", "three"]), "arrays").unwrap();
+            assert_eq!(messages(&entries), ["one", "two", "three"]);
+            assert_eq!(entries[1].get("__SEQNUM"), Some("2"));
+            assert_eq!(entries[1].realtime_usec(), Some(1_700_000_002_000_000));
+            assert!(entries[1].cursor().unwrap().starts_with("s=07070707070707070707070707070707;i=2;b=0101"));
+        }
+    }
+
+    #[test]
+    fn chained_arrays_are_followed_in_order() {
+        let mut fixture = Fixture::new(HEADER_INCOMPATIBLE_COMPACT, 7);
+        let entries: Vec<u64> = (1..=5).map(|n| fixture.entry(n, n, &n.to_string(), 0)).collect();
+        let first = fixture.array(&entries[..2], 2);
+        let second = fixture.array(&entries[2..], 4);
+        fixture.link(first, second);
+        fixture.set_entries(first, 5);
+
+        assert_eq!(messages(&read(&fixture, "chain").unwrap()), ["1", "2", "3", "4", "5"]);
+    }
+
+    #[test]
+    fn looping_arrays_stop_after_one_round() {
+        let mut fixture = Fixture::new(0, 7);
+        let entries: Vec<u64> = (1..=3).map(|n| fixture.entry(n, n, &n.to_string(), 0)).collect();
+        let first = fixture.array(&entries[..2], 2);
+        let second = fixture.array(&entries[2..], 1);
+        fixture.link(first, second);
+        fixture.link(second, first);
+        fixture.set_entries(first, 100); // Claims more entries than the chain holds
+
+        assert_eq!(messages(&read(&fixture, "loop").unwrap()), ["1", "2", "3"]);
+    }
+
+    #[test]
+    fn damaged_objects_are_rejected() {
+        // An entry array pointing at a data object keeps the entries before it
+        let mut fixture = Fixture::with_messages(0, &["one", "two"]);
+        let array = fixture.first_array();
+        let data = fixture.data("MESSAGE=three", 0);
+        fixture.link(array, data);
+        fixture.put_u64(152, 3);
+        assert_eq!(messages(&read(&fixture, "wrong-type").unwrap()), ["one", "two"]);
+
+        // An object smaller than its own header
+        let mut fixture = Fixture::with_messages(0, &["one"]);
+        let array = fixture.first_array();
+        fixture.put_u64(array + 8, 8);
+        assert!(read(&fixture, "tiny").is_err());
+
+        // Arrays past the end of the file
+        let mut fixture = Fixture::with_messages(0, &["one"]);
+        fixture.put_u64(176, 1 << 40);
+        assert!(read(&fixture, "past-end").is_err());
+    }
+
+    #[test]
+    fn each_compression_is_decoded() {
+        let message = "compressed ü ".repeat(40);
+        for (header, object) in [
+            (HEADER_INCOMPATIBLE_COMPRESSED_XZ, OBJECT_COMPRESSED_XZ),
+            (HEADER_INCOMPATIBLE_COMPRESSED_LZ4, OBJECT_COMPRESSED_LZ4),
+            (HEADER_INCOMPATIBLE_COMPRESSED_ZSTD, OBJECT_COMPRESSED_ZSTD),
+        ] {
+            let mut fixture = Fixture::new(header | HEADER_INCOMPATIBLE_COMPACT, 7);
+            let entry = fixture.entry(1, 1, &message, object);
+            let array = fixture.array(&[entry], 1);
+            fixture.set_entries(array, 1);
+
+            let entries = read(&fixture, "compressed").unwrap();
+            assert_eq!(messages(&entries), [message.as_str()], "flag {}", object);
+        }
+    }
+
+    #[test]
+    fn implausible_lz4_sizes_are_rejected() {
+        let mut fixture = Fixture::new(HEADER_INCOMPATIBLE_COMPRESSED_LZ4, 7);
+        let mut body = vec![0; 48];
+        body.extend_from_slice(&u64::MAX.to_le_bytes());
+        body.extend(lz4_flex::block::compress(b"MESSAGE=hi"));
+        let data = fixture.object(OBJECT_DATA, OBJECT_COMPRESSED_LZ4, &body);
+
+        let path = fixture.write("lz4-size");
+        let file = JournalFile::open(&path).unwrap();
+        fs::remove_file(path).unwrap();
+        let error = file.data_payload(data).unwrap_err();
+        assert!(error.to_string().contains("implausible size"));
+    }
+
+    #[test]
+    fn cache_reads_only_what_was_appended() {
+        let mut fixture = Fixture::new(0, 7);
+        let first = fixture.entry(1, 1, "one", 0);
+        let second = fixture.entry(2, 2, "two", 0);
+        let array = fixture.array(&[first, second], 3);
+        fixture.set_entries(array, 2);
+        let path = fixture.write("append");
+
+        let mut cache = JournalCache::default();
+        cache.update(std::slice::from_ref(&path)).unwrap();
+        assert_eq!(messages(&cache.entries), ["one", "two"]);
+
+        // Fill the free slot of the array, then start a second one
+        let third = fixture.entry(3, 3, "three", 0);
+        fixture.set_array_item(array, 2, third);
+        fixture.put_u64(152, 3);
+        fixture.write("append");
+        cache.update(std::slice::from_ref(&path)).unwrap();
+        assert_eq!(messages(&cache.entries), ["one", "two", "three"]);
+
+        let fourth = fixture.entry(4, 4, "four", 0);
+        let next = fixture.array(&[fourth], 4);
+        fixture.link(array, next);
+        fixture.put_u64(152, 4);
+        fixture.write("append");
+        cache.update(std::slice::from_ref(&path)).unwrap();
+        cache.update(std::slice::from_ref(&path)).unwrap();
+        fs::remove_file(path).unwrap();
+        assert_eq!(messages(&cache.entries), ["one", "two", "three", "four"]);
+    }
+
+    #[test]
+    fn cache_merges_files_by_sequence_number() {
+        // Entry 2 was logged after the clock went back, journalctl still shows it second
+        let mut fixture = Fixture::new(0, 7);
+        let entries = [fixture.entry(1, 100, "one", 0), fixture.entry(3, 300, "three", 0)];
+        let array = fixture.array(&entries, 2);
+        fixture.set_entries(array, 2);
+        let odd = fixture.write("odd");
+
+        let mut fixture = Fixture::new(0, 7);
+        let entries = [fixture.entry(2, 50, "two", 0), fixture.entry(3, 300, "three", 0)];
+        let array = fixture.array(&entries, 2);
+        fixture.set_entries(array, 2);
+        let even = fixture.write("even");
+
+        // Another journald instance, only comparable by time
+        let mut fixture = Fixture::new(0, 9);
+        let entry = fixture.entry(1, 200, "other", 0);
+        let array = fixture.array(&[entry], 1);
+        fixture.set_entries(array, 1);
+        let other = fixture.write("other");
+
+        let mut cache = JournalCache::default();
+        cache.update(&[odd.clone(), even.clone(), other.clone()]).unwrap();
+        for path in [odd, even, other] {
+            fs::remove_file(path).unwrap();
+        }
+        // The copy of entry 3 in the second file is the same entry
+        assert_eq!(messages(&cache.entries), ["one", "two", "other", "three"]);
+    }
+}
@@ -0,0 +1,162 @@
+use std::collections::HashMap;
+use std::process::Command;
+use std::sync::Mutex;
+use crate::jview_debug;
+use crate::jview_entry::JournalEntry;
+use crate::jview_error::{Result, SourceError};
+use crate::jview_follow::LogFollower;
+use crate::jview_source::{self, BootInfo, LogQuery, LogSource, UnitAction, UnitInfo};
+
+/// Reads the journal through the `journalctl` and `systemctl` commands.
+#[derive(Debug, Default)]
+pub struct JournalctlSource {
+    warning: Mutex<(String, bool)>, // Last warning journalctl printed, and whether it is yet to be shown
+}
+
+impl JournalctlSource {
+    pub fn new() -> Self {
+        JournalctlSource::default()
+    }
+
+    /// Runs journalctl and keeps what it warned about for `take_warning`.
+    fn journalctl(&self, args: &[String]) -> Result<String> {
+        let (stdout, stderr) = command_output("journalctl", args)?;
+        let warning = stderr.split_whitespace().collect::<Vec<_>>().join(" ");
+
+        // journalctl repeats its hints on every run, show each one once
+        let mut last = self.warning.lock().unwrap();
+        if !warning.is_empty() && warning != last.0 {
+            *last = (warning, true);
+        }
+        Ok(stdout)
+    }
+}
+
+/// Runs a command and returns its standard output and error output, or an
+/// error describing the failure.
+fn command_output(program: &str, args: &[String]) -> Result<(String, String)> {
+    jview_debug::log_debug_info("Doing command:", format_args!("{} {:?}", program, args));
+    let output = Command::new(program)
+        .args(args)
+        .output()
+        .map_err(|e| SourceError::from_spawn(program, e))?;
+
+    let stderr = String::from_utf8_lossy(&output.stderr).into_owned();
+    if output.status.success() {
+        Ok((String::from_utf8_lossy(&output.stdout).into_owned(), stderr))
+    } else {
+        Err(SourceError::from_stderr(program, &stderr))
+    }
+}
+
+/// Reads the JSON array printed by `systemctl list-units --output=json`.
+fn parse_units(stdout: &str) -> Result<Vec<UnitInfo>> {
+    let units: Vec<serde_json::Value> = serde_json::from_str(stdout)
+        .map_err(|e| SourceError::Io(format!("systemctl list-units: {}", e)))?;
+    let field = |unit: &serde_json::Value, name: &str| unit[name].as_str().unwrap_or_default().to_string();
+
+    let mut units: Vec<UnitInfo> = units
+        .iter()
+        .filter(|unit| unit["unit"].is_string())
+        .map(|unit| UnitInfo {
+            name: field(unit, "unit"),
+            load: field(unit, "load"),
+            active: field(unit, "active"),
+            sub: field(unit, "sub"),
+            description: field(unit, "description"),
+        })
+        .collect();
+    units.sort_by(|a, b| a.name.cmp(&b.name));
+    units.dedup_by(|a, b| a.name == b.name);
+    Ok(units)
+}
+
+impl LogSource for JournalctlSource {
+    fn list_units(&self, user: bool) -> Result<Vec<UnitInfo>> {
+        let scope = if user { "--user" } else { "--system" };
+        let field = if user { "_SYSTEMD_USER_UNIT" } else { "_SYSTEMD_UNIT" };
+        let listed = command_output(
+            "systemctl",
+            &[scope, "list-units", "--all", "--no-pager", "--output=json"].map(String::from),
+        );
+
+        match listed {
+            Ok((stdout, _)) => parse_units(&stdout),
+            // No usable systemd manager (e.g. in a container), fall back to what the journal knows
+            Err(_) => Ok(jview_source::named_units(
+                self.journalctl(&[scope, "-F", field].map(String::from))?
+                    .lines()
+                    .map(|unit| unit.to_string()),
+            )),
+        }
+    }
+
+    fn query(&self, query: &LogQuery) -> Result<Vec<JournalEntry>> {
+        let mut jargs = vec!["-o".to_string(), "json".to_string()];
+
+        if let Some(cursor) = &query.after_cursor {
+            jargs.push(format!("--after-cursor={}", cursor));
+        }
+        jargs.extend(query.journalctl_args());
+
+        Ok(self
+            .journalctl(&jargs)?
+            .lines()
+            .filter_map(JournalEntry::from_json)
+            .collect())
+    }
+
+    fn follow(&self, query: &LogQuery) -> Result<LogFollower> {
+        LogFollower::journalctl(&query.journalctl_args(), query.after_cursor.as_deref())
+    }
+
+    fn is_live(&self) -> bool {
+        true
+    }
+
+    fn take_warning(&self) -> Option<String> {
+        let mut last = self.warning.lock().unwrap();
+        let shown = std::mem::replace(&mut last.1, false);
+        shown.then(|| format!("journalctl: {}", last.0))
+    }
+
+    fn list_boots(&self, user: bool, namespace: Option<&str>) -> Result<Vec<BootInfo>> {
+        // Boot offsets count the boots of the journal the logs are read from
+        let mut jargs = ["--list-boots", "-o", "json"].map(String::from).to_vec();
+        jargs.extend(LogQuery::scope(user, namespace).journalctl_args());
+        let stdout = self.journalctl(&jargs)?;
+        let boots: Vec<serde_json::Value> = serde_json::from_str(&stdout)
+            .map_err(|e| SourceError::Io(format!("journalctl --list-boots: {}", e)))?;
+
+        Ok(boots
+            .iter()
+            .filter_map(|boot| {
+                Some(BootInfo {
+                    index: boot["index"].as_i64()?,
+                    id: boot["boot_id"].as_str()?.to_string(),
+                    first_usec: boot["first_entry"].as_u64()?,
+                    last_usec: boot["last_entry"].as_u64()?,
+                })
+            })
+            .collect())
+    }
+
+    fn unit_status(&self, unit: &str, user: bool, properties: &[&str]) -> Result<HashMap<String, String>> {
+        let scope = if user { "--user" } else { "--system" };
+        let args = [scope, "show", "--no-pager", &format!("--property={}", properties.join(",")), "--", unit];
+        let (stdout, _) = command_output("systemctl", &args.map(String::from))?;
+
+        Ok(stdout
+            .lines()
+            .filter_map(|line| line.split_once('='))
+            .map(|(name, value)| (name.to_string(), value.to_string()))
+            .collect())
+    }
+
+    fn unit_action(&self, unit: &str, user: bool, action: UnitAction) -> Result<()> {
+        let scope = if user { "--user" } else { "--system" };
+        // A polkit password prompt would write over the UI, fail instead
+        let args = [scope, action.verb(), "--no-ask-password", "--", unit];
+        command_output("systemctl", &args.map(String::from)).map(|_| ())
+    }
+}
@@ -1,67 +1,547 @@
-use std::{process::Command};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 use ratatui::{
-    style::{Style, Color},
-    widgets::{Block, Borders, List, ListItem},
+    style::{Style, Color, Modifier},
+    text::{Line, Span},
+    widgets::{Block, Borders, List, ListItem, Paragraph},
 };
 use ratatui::style;
-use crossterm::event::{self, Event, KeyCode};
+use crossterm::event::{self, Event, KeyCode, KeyModifiers};
 #[allow(unused_imports)]
 use crate::jview_config;
+use crate::jview_boots::{self, JviewBoots};
 use crate::jview_config::settings;
 use crate::jview_debug;
+use crate::jview_detail::JviewDetail;
+use crate::jview_entry::JournalEntry;
+use crate::jview_error::{self, SourceError};
+use crate::jview_filter::{LogFilter, SearchMode};
+use crate::jview_query::{Query, PRIORITY_NAMES};
+use crate::jview_follow::LogFollower;
+use crate::jview_prompt::{JviewPrompt, PromptAction};
+use crate::jview_source::{self, LogQuery, LogSource};
+use crate::jview_text;
+use crate::jview_theme;
+use crate::jview_time;
+use crate::jview_timerange::JviewTimeRange;
 
-#[derive(Debug, Clone, PartialEq, Eq, Copy)]
+/// How often the buffer asks the source for entries newer than the last one it holds.
+const REFRESH_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Columns taken by the priority badge in front of each line.
+const BADGE_WIDTH: usize = 8;
+
+/// Widest unit tag in front of each line when several units are shown together.
+const MAX_TAG_WIDTH: usize = 16;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FollowMode {
+    Off,       // Static view, refreshed every REFRESH_INTERVAL
+    Following, // Live tail of the journal
+    Paused,    // Live tail on hold, catches up when resumed
+}
+
+#[derive(Debug)]
 pub struct JviewLogs {
+    source: Arc<dyn LogSource>,
+    selected_idx: usize, // Cursor row, an index into `visible`
     vertical_start: usize,
     horizontal_start: usize,
     max_viewer_height: usize,
     max_viewer_width: usize,
+    entries: Vec<JournalEntry>,
+    loaded: Option<LogQuery>, // Query the buffer was loaded for
+    last_refresh: Instant,
+    follow_mode: FollowMode,
+    follower: Option<LogFollower>,
+    search: (String, SearchMode), // Search the query was parsed from
+    query: Query,
+    visible: Vec<usize>, // Indexes into `entries` that pass the query
+    detail: Option<JviewDetail>, // Fields of the entry under the cursor, while shown
+    error: Option<SourceError>,  // Why the logs could not be read, until dismissed or retried
+    show_badges: bool,           // Priority name in front of each line
+    wrap: bool,                  // Wrap long lines instead of scrolling horizontally
+    goto: Option<JviewPrompt>,   // Go to timestamp prompt, while shown
+    time_range: Option<JviewTimeRange>, // Time range picker, while shown
+    boots: Option<JviewBoots>,          // Boot list, while shown
+    find_text: (String, SearchMode), // Pattern the matches were found for
+    find: Option<LogFilter>,         // Pattern to jump between with n/N, lines stay visible
+    find_matches: Vec<usize>,        // Indexes into `visible` of the lines that match it
+    find_backward: bool,             // Started with `?`, so n goes towards older lines
+    find_origin: usize,              // Cursor when the pattern was started, to search from while typing
 }
 
 impl JviewLogs {
-    pub fn new() -> Self {
+    pub fn new(source: Arc<dyn LogSource>) -> Self {
         JviewLogs {
+            source,
+            selected_idx: 0,
             vertical_start: 0,
             horizontal_start: 0,
             max_viewer_height: 25,
             max_viewer_width: 25,
+            entries: Vec::new(),
+            loaded: None,
+            last_refresh: Instant::now(),
+            follow_mode: FollowMode::Off,
+            follower: None,
+            search: (String::new(), SearchMode::Text),
+            query: Query::default(),
+            visible: Vec::new(),
+            detail: None,
+            error: None,
+            show_badges: false,
+            wrap: false,
+            goto: None,
+            time_range: None,
+            boots: None,
+            find_text: (String::new(), SearchMode::Text),
+            find: None,
+            find_matches: Vec::new(),
+            find_backward: false,
+            find_origin: 0,
         }
     }
 
     pub fn set_max_height(&mut self, h: usize) {
-        self.max_viewer_height = h;
+        if h != self.max_viewer_height {
+            self.max_viewer_height = h;
+            self.clamp_view();
+        }
     }
-}
 
-pub fn fetch_journalctl_logs() -> Vec<String> {
-    let chosen = settings::get_unit();
-    let mut jargs = vec![];
+    pub fn set_max_width(&mut self, w: usize) {
+        if w != self.max_viewer_width {
+            self.max_viewer_width = w;
+            self.clamp_view();
+        }
+    }
 
-    if !chosen.is_empty() {
-        jview_debug::log_debug_info("Found Selected Unit to filter:", format_args!("{}", chosen));
-        jargs.push("-u".to_string());
-        jargs.push(chosen);
-    } else {
-        jargs.push("--since=yesterday".to_string());
+    /// Number of log lines that fit inside the borders of the pane.
+    fn visible_rows(&self) -> usize {
+        self.max_viewer_height.saturating_sub(2)
     }
 
-    jview_debug::log_debug_info("Doing command:", format_args!("{:?}", jargs));
-    let output = Command::new("journalctl")
-        .args(&jargs)
-        .output()
-        .expect("Failed to run journalctl command");
+    fn at_bottom(&self) -> bool {
+        self.selected_idx + 1 >= self.visible.len()
+    }
 
-    if output.status.success() {
-        //jview_debug::log_debug_info("Command output: ", format_args!("{}", String::from_utf8_lossy(&output.stdout)));
-        String::from_utf8_lossy(&output.stdout)
-            .lines()
-            .map(|line| line.to_string())
-            .collect()
-    } else {
-        vec!["Error fetching logs".to_string()]
+    fn scroll_to_bottom(&mut self) {
+        self.selected_idx = self.visible.len().saturating_sub(1);
+        self.vertical_start = self.first_fitting(self.selected_idx);
+    }
+
+    fn scroll_to_top(&mut self) {
+        self.selected_idx = 0;
+        self.vertical_start = 0;
+    }
+
+    /// Scrolls just enough to keep the cursor row on screen.
+    fn scroll_to_selected(&mut self) {
+        if self.selected_idx < self.vertical_start {
+            self.vertical_start = self.selected_idx;
+        } else {
+            self.vertical_start = self.vertical_start.max(self.first_fitting(self.selected_idx));
+        }
+    }
+
+    /// Moves the cursor by `delta` lines, stopping at the first and last line.
+    fn move_cursor(&mut self, delta: isize) {
+        let last = self.visible.len().saturating_sub(1);
+        self.selected_idx = self.selected_idx.saturating_add_signed(delta).min(last);
+        self.scroll_to_selected();
+    }
+
+    /// Scrolls the view and the cursor together by `delta` lines, like less does
+    /// for page and half page moves.
+    fn scroll_by(&mut self, delta: isize) {
+        let last = self.visible.len().saturating_sub(1);
+        self.vertical_start = self.vertical_start.saturating_add_signed(delta).min(self.first_fitting(last));
+        self.move_cursor(delta);
+    }
+
+    /// Brings the cursor and the view back inside the buffer after it or the
+    /// viewport has shrunk, without leaving empty rows below the last line.
+    fn clamp_view(&mut self) {
+        let last = self.visible.len().saturating_sub(1);
+        self.selected_idx = self.selected_idx.min(last);
+        self.vertical_start = self.vertical_start.min(self.first_fitting(last));
+        self.scroll_to_selected();
+    }
+
+    /// Moves the cursor to the first line logged at or after `text`, shown at
+    /// the top of the pane.
+    ///
+    /// # Arguments
+    ///
+    /// * `text` - A time in any form `--since` takes, like `14:30` or `2h ago`.
+    ///
+    /// # Returns
+    ///
+    /// A message for the prompt when the time is invalid or nothing was logged
+    /// after it.
+    fn go_to_time(&mut self, text: &str) -> Result<(), String> {
+        let usec = jview_time::parse_time(text).ok_or_else(|| format!("Invalid time '{}'", text))?;
+        // Entries come in journal order, oldest first
+        let idx = self.visible.partition_point(|&i| {
+            self.entries[i].realtime_usec().is_some_and(|t| (t as i64) < usec)
+        });
+        if idx >= self.visible.len() {
+            return Err("No entries at or after that time".to_string());
+        }
+
+        self.selected_idx = idx;
+        self.vertical_start = idx.min(self.first_fitting(self.visible.len() - 1));
+        Ok(())
+    }
+
+    /// Earliest visible line that still leaves room for line `last` at the bottom.
+    fn first_fitting(&self, last: usize) -> usize {
+        if !self.wrap {
+            return (last + 1).saturating_sub(self.visible_rows());
+        }
+
+        let mut first = last;
+        let mut rows = self.entry_rows(last);
+        while first > 0 {
+            let above = self.entry_rows(first - 1);
+            if rows + above > self.visible_rows() {
+                break;
+            }
+            rows += above;
+            first -= 1;
+        }
+        first
+    }
+
+    /// Columns left for the text of a line inside the borders, badge and tag columns.
+    fn text_width(&self) -> usize {
+        let badge = if self.show_badges { BADGE_WIDTH } else { 0 };
+        self.max_viewer_width.saturating_sub(2 + badge + tag_width(&settings::get_units()))
+    }
+
+    /// Screen rows taken by visible line `idx`.
+    fn entry_rows(&self, idx: usize) -> usize {
+        match self.visible.get(idx) {
+            Some(&i) if self.wrap => jview_text::wrap_columns(&self.entries[i].to_line(), self.text_width()).len(),
+            _ => 1,
+        }
+    }
+
+    fn is_visible(&self, entry: &JournalEntry) -> bool {
+        self.query.matches(entry)
+    }
+
+    /// Replaces the buffer and re-applies the filter to all of it.
+    fn set_entries(&mut self, entries: Vec<JournalEntry>) {
+        self.entries = entries;
+        self.visible.clear();
+        self.find_matches.clear();
+        self.append_visible(0);
+    }
+
+    /// Adds new entries to the end of the buffer, filtering only the new ones.
+    fn append(&mut self, newer: Vec<JournalEntry>) {
+        let first_new = self.entries.len();
+        self.entries.extend(newer);
+        self.append_visible(first_new);
+        self.trim();
+    }
+
+    /// Drops the oldest entries once the buffer holds more than `MAX_ENTRIES`,
+    /// keeping the cursor and the view on the same lines.
+    fn trim(&mut self) {
+        let excess = self.entries.len().saturating_sub(jview_source::MAX_ENTRIES);
+        if excess == 0 {
+            return;
+        }
+
+        self.entries.drain(..excess);
+        let dropped = self.visible.partition_point(|&i| i < excess);
+        self.visible.drain(..dropped);
+        self.visible.iter_mut().for_each(|i| *i -= excess);
+
+        let found = self.find_matches.partition_point(|&idx| idx < dropped);
+        self.find_matches.drain(..found);
+        self.find_matches.iter_mut().for_each(|idx| *idx -= dropped);
+
+        self.selected_idx = self.selected_idx.saturating_sub(dropped);
+        self.vertical_start = self.vertical_start.saturating_sub(dropped);
+        self.find_origin = self.find_origin.saturating_sub(dropped);
+    }
+
+    fn append_visible(&mut self, from: usize) {
+        for i in from..self.entries.len() {
+            if self.is_visible(&self.entries[i]) {
+                self.visible.push(i);
+                if self.find.as_ref().is_some_and(|f| f.matches(&self.entries[i].to_line())) {
+                    self.find_matches.push(self.visible.len() - 1);
+                }
+            }
+        }
+    }
+
+    /// Re-parses the query when the search text has changed.
+    fn sync_filter(&mut self) {
+        let search = settings::get_search();
+        if search == self.search {
+            return;
+        }
+
+        self.query = search_query();
+        self.search = search;
+        self.visible.clear();
+        self.find_matches.clear();
+        self.append_visible(0);
+        self.scroll_to_top();
+        if self.follow_mode == FollowMode::Following {
+            self.scroll_to_bottom();
+        }
+    }
+
+    /// Picks up the pattern being typed after `/` or `?` and jumps to its first
+    /// match from where the search started, like incremental search in less.
+    fn sync_find(&mut self) {
+        let find = settings::get_find();
+        if find == self.find_text {
+            return;
+        }
+
+        // The search box only publishes patterns that compile
+        self.find = LogFilter::new(&find.0, find.1).ok().filter(|_| !find.0.is_empty());
+        self.find_text = find;
+        self.find_matches = match &self.find {
+            Some(filter) => (0..self.visible.len())
+                .filter(|&idx| filter.matches(&self.entries[self.visible[idx]].to_line()))
+                .collect(),
+            None => Vec::new(),
+        };
+
+        let origin = self.find_origin;
+        let found = if self.find_backward {
+            self.find_matches.iter().rev().find(|&&idx| idx <= origin)
+        } else {
+            self.find_matches.iter().find(|&&idx| idx >= origin)
+        };
+        self.selected_idx = found.copied().unwrap_or(origin).min(self.visible.len().saturating_sub(1));
+        self.scroll_to_selected();
+    }
+
+    /// Remembers where a new `/` or `?` search starts from.
+    pub fn begin_find(&mut self, backward: bool) {
+        self.find_backward = backward;
+        self.find_origin = self.selected_idx;
+    }
+
+    /// Moves the cursor to the next match in the search direction, or against it
+    /// for `N`. Stays put when there is no match that way.
+    fn next_match(&mut self, reverse: bool) {
+        let found = if self.find_backward != reverse {
+            self.find_matches.iter().rev().find(|&&idx| idx < self.selected_idx)
+        } else {
+            self.find_matches.iter().find(|&&idx| idx > self.selected_idx)
+        };
+        if let Some(&idx) = found {
+            self.selected_idx = idx;
+            self.scroll_to_selected();
+        }
+    }
+
+    fn current_query(&self) -> LogQuery {
+        log_query()
+    }
+
+    /// Starts a follower that picks up right after the newest buffered entry.
+    fn start_follower(&mut self) {
+        let query = LogQuery {
+            after_cursor: self.entries.last().and_then(|e| e.cursor()).map(|c| c.to_string()),
+            ..self.current_query()
+        };
+
+        match self.source.follow(&query) {
+            Ok(follower) => self.follower = Some(follower),
+            Err(e) => {
+                jview_debug::log_debug_info("Failed to follow journal: ", format_args!("{}", e));
+                settings::set_message(&format!("Could not follow the journal: {}", e.describe()), true);
+                self.follow_mode = FollowMode::Off;
+            }
+        }
+    }
+
+    /// Starts in follow mode, the follower is started once the buffer is loaded.
+    pub fn set_following(&mut self) {
+        self.follow_mode = FollowMode::Following;
+    }
+
+    /// Switches to follow mode at the newest entries, e.g. to watch a unit that
+    /// was just restarted.
+    pub fn follow_latest(&mut self) {
+        if self.follow_mode != FollowMode::Following {
+            self.follow_mode = FollowMode::Following;
+            // A changed query is reloaded and followed by the next refresh
+            if self.loaded.as_ref() == Some(&self.current_query()) {
+                self.start_follower();
+            }
+        }
+        self.scroll_to_bottom();
+    }
+
+    pub fn toggle_badges(&mut self) {
+        self.show_badges = !self.show_badges;
+    }
+
+    /// Switches between wrapping long lines and scrolling them horizontally.
+    pub fn toggle_wrap(&mut self) {
+        self.wrap = !self.wrap;
+        self.horizontal_start = 0;
+        self.scroll_to_selected();
+    }
+
+    /// Tightens the minimum priority one step per press: info, notice, warning and
+    /// so on up to emerg, then back to showing everything.
+    pub fn cycle_priority(&mut self) {
+        let next = match settings::get_priority() {
+            None | Some((_, 7)) => Some(6),
+            Some((_, 0)) => None,
+            Some((_, high)) => Some(high - 1),
+        };
+        settings::set_priority(next.map(|high| (0, high)));
+    }
+
+    /// Switches live follow mode on or off.
+    pub fn toggle_follow(&mut self) {
+        if self.follow_mode == FollowMode::Off {
+            self.follow_mode = FollowMode::Following;
+            self.scroll_to_bottom();
+            self.start_follower();
+        } else {
+            self.follow_mode = FollowMode::Off;
+            self.follower = None;
+            self.last_refresh = Instant::now();
+        }
+    }
+
+    /// Holds or resumes the live tail while follow mode is on.
+    pub fn toggle_pause(&mut self) {
+        match self.follow_mode {
+            FollowMode::Following => {
+                self.follow_mode = FollowMode::Paused;
+                self.follower = None;
+            }
+            FollowMode::Paused => {
+                self.follow_mode = FollowMode::Following;
+                self.start_follower();
+            }
+            FollowMode::Off => {}
+        }
+    }
+
+    /// Keeps the log buffer in sync with the journal.
+    ///
+    /// The buffer is reloaded from scratch when the selected unit, the time window,
+    /// the boot, the priorities or the journal matches of the submitted query
+    /// change, and the visible lines are recomputed when the search text changes.
+    /// In follow mode new entries come from the background follower and the view
+    /// sticks to the bottom; otherwise only entries written after the last buffered
+    /// cursor are appended, at most once every `REFRESH_INTERVAL`.
+    pub fn refresh(&mut self) {
+        let wanted = self.current_query();
+
+        // Warnings from earlier reads, e.g. that only the user's own entries are visible
+        if let Some(warning) = self.source.take_warning() {
+            settings::set_message(&warning, false);
+        }
+
+        self.sync_filter();
+        self.sync_find();
+
+        if self.loaded.as_ref() != Some(&wanted) {
+            if !wanted.units.is_empty() {
+                jview_debug::log_debug_info("Found Selected Units to filter:", format_args!("{:?}", wanted.units));
+            }
+            let entries = self.source.query(&wanted).unwrap_or_else(|e| {
+                jview_debug::log_debug_info("Failed to read logs: ", format_args!("{}", e));
+                self.error = Some(e);
+                Vec::new()
+            });
+            self.set_entries(entries);
+            self.loaded = Some(wanted);
+            self.scroll_to_top();
+            self.last_refresh = Instant::now();
+            if self.follow_mode == FollowMode::Following {
+                self.scroll_to_bottom();
+                self.start_follower();
+            }
+            return;
+        }
+
+        match self.follow_mode {
+            FollowMode::Following => {
+                let (newer, failure) = match self.follower.as_mut() {
+                    Some(follower) => (follower.drain(), follower.take_error()),
+                    None => (Vec::new(), None),
+                };
+                if !newer.is_empty() {
+                    let pinned = self.at_bottom();
+                    self.append(newer);
+                    if pinned {
+                        self.scroll_to_bottom();
+                    }
+                }
+                if let Some(e) = failure {
+                    // Nothing arrives any more, say why instead of showing [FOLLOW]
+                    jview_debug::log_debug_info("Follower failed: ", format_args!("{}", e));
+                    self.follower = None;
+                    self.follow_mode = FollowMode::Off;
+                    self.error = Some(e);
+                }
+                return;
+            }
+            FollowMode::Paused => return,
+            FollowMode::Off => {}
+        }
+
+        if self.last_refresh.elapsed() < REFRESH_INTERVAL {
+            return;
+        }
+
+        let cursor = self.entries.last().and_then(|e| e.cursor()).map(|c| c.to_string());
+        if let Some(cursor) = cursor {
+            let query = LogQuery {
+                after_cursor: Some(cursor),
+                ..wanted
+            };
+            // On failure keep what is already buffered and try again next time
+            if let Ok(newer) = self.source.query(&query) {
+                self.append(newer);
+            }
+        }
+        self.last_refresh = Instant::now();
+    }
+}
+
+/// What the logs pane wants from the source, from the unit and filters in the settings.
+pub fn log_query() -> LogQuery {
+    LogQuery {
+        units: settings::get_units(),
+        user: settings::is_user_scope(),
+        namespace: settings::get_namespace(),
+        since: settings::get_since(),
+        until: settings::get_until(),
+        boot: settings::get_boot(),
+        priority: settings::get_priority(),
+        matches: settings::get_matches(),
+        after_cursor: None,
     }
 }
 
+/// The search query the logs pane filters with, from the settings.
+pub fn search_query() -> Query {
+    let (text, mode) = settings::get_search();
+    // The search box only publishes queries that parse
+    Query::parse(&text, mode).unwrap_or_default()
+}
+
 pub fn get_style(selected: bool) -> style::Style {
     if selected {
         Style::default()
@@ -74,29 +554,113 @@ pub fn get_style(selected: bool) -> style::Style {
     }
 }
 
+/// Columns taken by the unit tag in front of each line, which is only shown to
+/// tell apart several units shown together.
+fn tag_width(units: &[String]) -> usize {
+    if units.len() < 2 {
+        return 0;
+    }
+    units.iter().map(|u| u.chars().count()).max().unwrap_or(0).min(MAX_TAG_WIDTH) + 1
+}
+
+/// Splits a line into spans so the given byte ranges stand out.
+fn highlight_line(line: &str, ranges: &[(usize, usize)]) -> Line<'static> {
+    let matched = Style::default()
+        .fg(Color::Black)
+        .bg(Color::Yellow)
+        .add_modifier(Modifier::BOLD);
+    let mut spans = Vec::new();
+    let mut pos = 0;
+
+    for &(start, end) in ranges {
+        if start > pos {
+            spans.push(Span::raw(line[pos..start].to_string()));
+        }
+        spans.push(Span::styled(line[start..end].to_string(), matched));
+        pos = end;
+    }
+    if pos < line.len() {
+        spans.push(Span::raw(line[pos..].to_string()));
+    }
+
+    Line::from(spans)
+}
+
+/// The parts of `ranges` that fall inside `start..end`, relative to `start`.
+fn clip_ranges(ranges: &[(usize, usize)], start: usize, end: usize) -> Vec<(usize, usize)> {
+    ranges
+        .iter()
+        .filter(|&&(s, e)| s < end && e > start)
+        .map(|&(s, e)| (s.max(start) - start, e.min(end) - start))
+        .collect()
+}
+
 impl JviewLogs {
-    fn get_log_items(self, selected: bool) -> Vec<ListItem<'static>> {
-        let logs = fetch_journalctl_logs();
-        let mut log_items: Vec<ListItem> = Vec::new(); // Viewable
+    /// Byte ranges of `line` matched by the filter or by the pattern to find,
+    /// sorted and non-overlapping.
+    fn highlight_ranges(&self, line: &str) -> Vec<(usize, usize)> {
+        let mut ranges = self.query.highlight_ranges(line);
+        if let Some(find) = &self.find {
+            ranges.extend(find.find_ranges(line));
+        }
+        jview_text::merge_ranges(ranges)
+    }
 
-        for (i, line) in logs.iter().enumerate() {
-            if i < self.vertical_start {
-                continue; // Skip lines until the vertical offset
-            }
+    fn get_log_items(&self, selected: bool) -> Vec<ListItem<'static>> {
+        let mut log_items: Vec<ListItem> = Vec::new(); // Viewable
+        let theme = settings::get_theme();
+        let units = settings::get_units();
+        let tag_width = tag_width(&units);
 
+        // Skip lines until the vertical offset without walking the whole buffer
+        for (idx, &i) in self.visible.iter().enumerate().skip(self.vertical_start) {
             if log_items.len() >= self.max_viewer_height {
                 break; // Stop if we've taken enough lines to fit the section
             }
 
-            let visible_line = if line.len() > self.horizontal_start {
-                &line[self.horizontal_start..]
+            let priority = self.entries[i].priority();
+            let priority_style = theme.style(priority);
+            let style = if idx == self.selected_idx {
+                Style::default().fg(Color::Black).bg(Color::Cyan)
             } else {
-                ""
+                get_style(selected).patch(priority_style)
             };
 
-            let style = get_style(selected);
+            let line = self.entries[i].to_line();
+            let rows = if self.wrap {
+                let ranges = self.highlight_ranges(&line);
+                jview_text::wrap_columns(&line, self.text_width())
+                    .into_iter()
+                    .map(|(start, end)| highlight_line(&line[start..end], &clip_ranges(&ranges, start, end)))
+                    .collect()
+            } else {
+                let visible_line = &line[jview_text::skip_columns(&line, self.horizontal_start)..];
+                let ranges = self.highlight_ranges(visible_line);
+                vec![highlight_line(visible_line, &ranges)]
+            };
 
-            log_items.push(ListItem::new(visible_line.to_string()).style(style));
+            let unit = jview_source::entry_unit(&self.entries[i], &units, settings::is_user_scope());
+            for (n, mut row) in rows.into_iter().enumerate() {
+                if tag_width > 0 {
+                    // Colored like the unit in the selector, so interleaved units stand apart
+                    let name: String = unit.filter(|_| n == 0).unwrap_or("").chars().take(tag_width - 1).collect();
+                    let color = unit
+                        .and_then(|u| units.iter().position(|x| x == u))
+                        .map_or(Color::Reset, jview_theme::unit_color);
+                    let tag = Span::styled(
+                        format!("{:<width$}", name, width = tag_width),
+                        Style::default().fg(color).add_modifier(Modifier::BOLD),
+                    );
+                    row.spans.insert(0, tag);
+                }
+                if self.show_badges {
+                    let name = priority.and_then(|p| PRIORITY_NAMES.get(p as usize)).unwrap_or(&"");
+                    let badge = if n == 0 { *name } else { "" };
+                    let badge = Span::styled(format!("{:<width$}", badge, width = BADGE_WIDTH), get_style(selected).patch(priority_style));
+                    row.spans.insert(0, badge);
+                }
+                log_items.push(ListItem::new(row).style(style));
+            }
         }
 
         log_items
@@ -112,42 +676,169 @@ impl JviewLogs {
     ///
     /// A `List` widget configured with the provided parameters.
     pub fn get_logs_widget<'b>(&self, selected: bool) -> List<'b> {
-        let logitems: Vec<ListItem> = (*self.get_log_items(selected)).to_vec();
+        let logitems: Vec<ListItem> = self.get_log_items(selected);
+
+        let scope = jview_source::scope_label(settings::is_user_scope(), settings::get_namespace().as_deref());
+        let mut title = match self.follow_mode {
+            FollowMode::Off => format!("Logs ({})", scope),
+            FollowMode::Following => format!("Logs ({}) [FOLLOW]", scope),
+            FollowMode::Paused => format!("Logs ({}) [PAUSED]", scope),
+        };
+        if self.wrap {
+            title += " [WRAP]";
+        }
+        if let Some((low, high)) = settings::get_priority() {
+            let (low, high) = (PRIORITY_NAMES[low as usize], PRIORITY_NAMES[high as usize]);
+            if low == PRIORITY_NAMES[0] {
+                title += &format!(" [priority<={}]", high);
+            } else {
+                title += &format!(" [priority {}..{}]", low, high);
+            }
+        }
+        if !self.query.is_empty() {
+            title += &format!(" [{} of {} match]", self.visible.len(), self.entries.len());
+        }
+        if self.find.is_some() {
+            let key = if self.find_backward { '?' } else { '/' };
+            title += &match self.find_matches.binary_search(&self.selected_idx) {
+                Ok(n) => format!(" [{}{}: match {} of {}]", key, self.find_text.0, n + 1, self.find_matches.len()),
+                Err(_) => format!(" [{}{}: {} matches]", key, self.find_text.0, self.find_matches.len()),
+            };
+        }
 
         List::new(logitems)
-            .block(Block::default().borders(Borders::ALL).title("Logs"))
+            .block(Block::default().borders(Borders::ALL).title(title))
             .style(get_style(selected))
     }
 
+    /// The error reading the logs, until it is dismissed or the read is retried.
+    pub fn get_error_widget(&self) -> Option<Paragraph<'static>> {
+        self.error.as_ref().map(jview_error::get_error_widget)
+    }
+
+    /// The popup with all fields of the entry under the cursor, while it is open.
+    pub fn get_detail_widget(&self) -> Option<List<'static>> {
+        self.detail.as_ref().map(|d| d.get_detail_widget())
+    }
 
-    pub fn logs_navigate(&mut self) -> Result<KeyCode, std::io::Error> {
-        let logs = fetch_journalctl_logs();
+    pub fn set_detail_max_height(&mut self, h: usize) {
+        if let Some(detail) = self.detail.as_mut() {
+            detail.set_max_height(h);
+        }
+    }
+
+    /// The go to timestamp prompt or the prompt for a typed time bound, while open.
+    pub fn get_prompt_widget(&self) -> Option<Paragraph<'static>> {
+        match &self.time_range {
+            Some(time_range) => time_range.get_prompt_widget(),
+            None => self.goto.as_ref().map(|p| p.get_prompt_widget()),
+        }
+    }
+
+    /// The boot list, while it is open.
+    pub fn get_boots_widget(&self) -> Option<List<'static>> {
+        self.boots.as_ref().map(|b| b.get_boots_widget())
+    }
+
+    pub fn set_boots_max_height(&mut self, h: usize) {
+        if let Some(boots) = self.boots.as_mut() {
+            boots.set_max_height(h);
+        }
+    }
+
+    /// The time range picker, while it is open.
+    pub fn get_time_range_widget(&self) -> Option<List<'static>> {
+        self.time_range.as_ref().and_then(|t| t.get_widget())
+    }
 
+    pub fn logs_navigate(&mut self) -> Result<KeyCode, std::io::Error> {
         if let Event::Key(key) = event::read()? {
+            if self.error.is_some() {
+                match key.code {
+                    KeyCode::Char('q') | KeyCode::Char('Q') => return Ok(KeyCode::Char('q')),
+                    KeyCode::Tab => return Ok(KeyCode::Tab),
+                    KeyCode::Char('r') => {
+                        // Read again on the next refresh, e.g. after fixing the permissions
+                        self.error = None;
+                        self.loaded = None;
+                    }
+                    KeyCode::Esc | KeyCode::Enter => self.error = None,
+                    _ => {}
+                }
+                return Ok(KeyCode::Enter);
+            }
+            if let Some(detail) = self.detail.as_mut() {
+                if !detail.navigate(key) {
+                    self.detail = None;
+                }
+                return Ok(KeyCode::Enter);
+            }
+            if let Some(boots) = self.boots.as_mut() {
+                if !boots.navigate(key) {
+                    self.boots = None;
+                }
+                return Ok(KeyCode::Enter);
+            }
+            if let Some(time_range) = self.time_range.as_mut() {
+                if !time_range.navigate(key) {
+                    self.time_range = None;
+                }
+                return Ok(KeyCode::Enter);
+            }
+            if let Some(mut prompt) = self.goto.take() {
+                match prompt.handle_key(key) {
+                    PromptAction::Edit => self.goto = Some(prompt),
+                    PromptAction::Submit => {
+                        if let Err(e) = self.go_to_time(prompt.input().trim()) {
+                            prompt.set_error(&e);
+                            self.goto = Some(prompt);
+                        }
+                    }
+                    PromptAction::Cancel => {}
+                }
+                return Ok(KeyCode::Enter);
+            }
+
+            let page = self.visible_rows().max(1) as isize;
+            let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+
             match key.code {
                 KeyCode::Char('q') => return Ok(KeyCode::Char('q')),
                 KeyCode::Char('Q') => return Ok(KeyCode::Char('q')),
                 KeyCode::Tab => {
                     return Ok(KeyCode::Tab);
                 }
-                KeyCode::Up => {
-                    if self.vertical_start > 0 {
-                        self.vertical_start -= 1;
+                KeyCode::Char('u') if ctrl => self.scroll_by(-page / 2),
+                KeyCode::Char('d') if ctrl => self.scroll_by(page / 2),
+                KeyCode::Up | KeyCode::Char('k') => self.move_cursor(-1),
+                KeyCode::Down | KeyCode::Char('j') => self.move_cursor(1),
+                KeyCode::PageUp => self.scroll_by(-page),
+                KeyCode::PageDown => self.scroll_by(page),
+                KeyCode::Home | KeyCode::Char('g') => self.scroll_to_top(),
+                KeyCode::End | KeyCode::Char('G') => self.scroll_to_bottom(),
+                KeyCode::Char('/') | KeyCode::Char('?') | KeyCode::Char('u') => return Ok(key.code),
+                KeyCode::Char('n') => self.next_match(false),
+                KeyCode::Char('N') => self.next_match(true),
+                KeyCode::Char('b') => self.boots = Some(JviewBoots::new(self.source.as_ref())),
+                KeyCode::Char('B') => jview_boots::select_previous_boot(self.source.as_ref()),
+                KeyCode::Char('T') => self.time_range = Some(JviewTimeRange::new()),
+                KeyCode::Char('t') => self.goto = Some(JviewPrompt::new("Go to time (e.g. 14:30, 2h ago, 2024-05-01 08:00)")),
+                KeyCode::Enter => {
+                    if let Some(&i) = self.visible.get(self.selected_idx) {
+                        self.detail = Some(JviewDetail::new(&self.entries[i]));
                     }
                 }
-                KeyCode::Down => {
-                    if self.vertical_start < logs.len() {
-                        self.vertical_start += 1;
-                    }
-                }
-                KeyCode::Left => {
-                    if self.horizontal_start > 0 {
-                        self.horizontal_start -= 1;
-                    }
+                KeyCode::Left if self.horizontal_start > 0 => {
+                    self.horizontal_start -= 1;
                 }
-                KeyCode::Right => {
+                KeyCode::Right if !self.wrap => {
                     self.horizontal_start += 1;
                 }
+                KeyCode::Char('w') => self.toggle_wrap(),
+                KeyCode::Char('p') => self.cycle_priority(),
+                KeyCode::Char('P') => self.toggle_badges(),
+                KeyCode::Char('f') => self.toggle_follow(),
+                KeyCode::Char(' ') => self.toggle_pause(),
                 _ => {}
             }
         }
@@ -0,0 +1,164 @@
+use std::io::{self, BufWriter, Write};
+use std::thread;
+use std::time::Duration;
+use clap::ValueEnum;
+use crate::jview_entry::JournalEntry;
+use crate::jview_error;
+use crate::jview_logs;
+use crate::jview_source::{LogQuery, LogSource};
+
+/// How often `--print --follow` checks for new entries.
+const FOLLOW_POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Columns written by the CSV output, after a header row with these names.
+const CSV_COLUMNS: [&str; 7] = ["timestamp", "hostname", "identifier", "pid", "priority", "unit", "message"];
+
+/// How `--print` writes the entries.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum OutputFormat {
+    Text, // Same lines as the logs pane
+    Json, // One journalctl style JSON object per line
+    Csv,  // The main fields as comma separated values
+}
+
+/// Writes the entries the logs pane would show to stdout instead of starting the UI.
+///
+/// The unit, filters and search query are taken from the settings, exactly as the
+/// interactive logs pane does.
+///
+/// # Arguments
+///
+/// * `source` - Where the entries come from.
+/// * `format` - The output format.
+/// * `follow` - Keep printing new entries until interrupted.
+///
+/// # Returns
+///
+/// Ok once everything was written or the reader went away, or the error from the source.
+pub fn print_logs(source: &dyn LogSource, format: OutputFormat, follow: bool) -> jview_error::Result<()> {
+    let mut out = BufWriter::new(io::stdout().lock());
+
+    match write_logs(source, format, follow, &mut out).and_then(|_| out.flush()) {
+        Err(e) if e.kind() == io::ErrorKind::BrokenPipe => Ok(()), // e.g. piped into `head`
+        result => Ok(result?),
+    }
+}
+
+fn write_logs(source: &dyn LogSource, format: OutputFormat, follow: bool, out: &mut impl Write) -> io::Result<()> {
+    let query = jview_logs::log_query();
+    let search = jview_logs::search_query();
+    // Source errors travel as io::Error with the write errors, print_logs unwraps them
+    let entries = source.query(&query).map_err(io::Error::other)?;
+    if let Some(warning) = source.take_warning() {
+        eprintln!("{}", warning);
+    }
+
+    if format == OutputFormat::Csv {
+        writeln!(out, "{}", CSV_COLUMNS.join(","))?;
+    }
+    for entry in entries.iter().filter(|e| search.matches(e)) {
+        write_entry(out, entry, format)?;
+    }
+    if !follow {
+        return Ok(());
+    }
+
+    let mut follower = source.follow(&LogQuery {
+        after_cursor: entries.last().and_then(|e| e.cursor()).map(|c| c.to_string()),
+        ..query
+    })
+    .map_err(io::Error::other)?;
+    loop {
+        out.flush()?;
+        thread::sleep(FOLLOW_POLL_INTERVAL);
+        for entry in follower.drain().iter().filter(|e| search.matches(e)) {
+            write_entry(out, entry, format)?;
+        }
+        if let Some(e) = follower.take_error() {
+            return Err(io::Error::other(e));
+        }
+    }
+}
+
+fn write_entry(out: &mut impl Write, entry: &JournalEntry, format: OutputFormat) -> io::Result<()> {
+    match format {
+        OutputFormat::Text => writeln!(out, "{}", entry.to_line()),
+        OutputFormat::Json => writeln!(out, "{}", entry.to_json()),
+        OutputFormat::Csv => {
+            let timestamp = entry.iso_timestamp();
+            let priority = entry.priority().map(|p| p.to_string()).unwrap_or_default();
+            let fields = [
+                timestamp.as_str(),
+                entry.hostname().unwrap_or(""),
+                entry.identifier(),
+                entry.pid().unwrap_or(""),
+                priority.as_str(),
+                entry.unit().unwrap_or(""),
+                entry.message(),
+            ];
+            let row: Vec<String> = fields.iter().map(|f| csv_field(f)).collect();
+            writeln!(out, "{}", row.join(","))
+        }
+    }
+}
+
+/// Quotes a CSV field when it contains a separator, quote or line break.
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::jview_entry::fixtures::entry;
+
+    fn written(entry: &JournalEntry, format: OutputFormat) -> String {
+        let mut out = Vec::new();
+        write_entry(&mut out, entry, format).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    fn nginx() -> JournalEntry {
+        entry(&[
+            ("_HOSTNAME", "web1"),
+            ("SYSLOG_IDENTIFIER", "nginx"),
+            ("_PID", "42"),
+            ("PRIORITY", "3"),
+            ("_SYSTEMD_UNIT", "nginx.service"),
+            ("MESSAGE", "upstream \"api\" down, retrying"),
+        ])
+    }
+
+    #[test]
+    fn csv_quotes_only_fields_that_need_it() {
+        assert_eq!(csv_field("plain"), "plain");
+        assert_eq!(csv_field("a,b"), "\"a,b\"");
+        assert_eq!(csv_field("say \"hi\""), "\"say \"\"hi\"\"\"");
+        assert_eq!(csv_field("two\nlines"), "\"two\nlines\"");
+    }
+
+    #[test]
+    fn csv_rows_follow_the_header() {
+        assert_eq!(
+            written(&nginx(), OutputFormat::Csv),
+            ",web1,nginx,42,3,nginx.service,\"upstream \"\"api\"\" down, retrying\"\n"
+        );
+    }
+
+    #[test]
+    fn json_lines_keep_every_field() {
+        let line = written(&nginx(), OutputFormat::Json);
+        assert!(line.ends_with("}\n") && !line.trim_end().contains('\n'));
+        assert_eq!(JournalEntry::from_json(&line), Some(nginx()));
+    }
+
+    #[test]
+    fn text_lines_match_the_logs_pane() {
+        assert_eq!(written(&nginx(), OutputFormat::Text), format!("{}\n", nginx().to_line()));
+        assert!(written(&nginx(), OutputFormat::Text).contains(" web1 nginx[42]: upstream"));
+    }
+}
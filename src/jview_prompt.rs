@@ -0,0 +1,88 @@
+use ratatui::{
+    layout::Rect,
+    style::{Color, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Paragraph},
+};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+/// What a key press did to a prompt.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PromptAction {
+    Edit,   // Input changed or nothing happened, keep the prompt open
+    Submit, // Enter, use the input
+    Cancel, // Esc, close without using the input
+}
+
+/// A one line text input shown over a pane, like the `:` prompt of an editor.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JviewPrompt {
+    label: String,
+    input: String,
+    error: Option<String>, // Why the last submitted input was rejected
+}
+
+impl JviewPrompt {
+    pub fn new(label: &str) -> Self {
+        JviewPrompt {
+            label: label.to_string(),
+            input: String::new(),
+            error: None,
+        }
+    }
+
+    pub fn input(&self) -> &str {
+        &self.input
+    }
+
+    /// Starts the prompt with a value to edit.
+    pub fn set_input(&mut self, input: &str) {
+        self.input = input.to_string();
+    }
+
+    /// Keeps the prompt open with a message about the rejected input.
+    pub fn set_error(&mut self, error: &str) {
+        self.error = Some(error.to_string());
+    }
+
+    pub fn handle_key(&mut self, key: KeyEvent) -> PromptAction {
+        self.error = None;
+        match key.code {
+            KeyCode::Esc => return PromptAction::Cancel,
+            KeyCode::Enter => return PromptAction::Submit,
+            KeyCode::Backspace => {
+                self.input.pop();
+            }
+            KeyCode::Char('u') if key.modifiers.contains(KeyModifiers::CONTROL) => self.input.clear(),
+            KeyCode::Char(c) => self.input.push(c),
+            _ => {}
+        }
+        PromptAction::Edit
+    }
+
+    /// Creates the prompt widget.
+    ///
+    /// # Returns
+    ///
+    /// A `Paragraph` widget showing the label, the input and any error.
+    pub fn get_prompt_widget(&self) -> Paragraph<'static> {
+        let mut title = vec![Span::raw(format!("{} [Enter]: OK | [Esc]: Cancel", self.label))];
+        if let Some(e) = &self.error {
+            title.push(Span::styled(format!(" {} ", e), Style::default().fg(Color::White).bg(Color::Red)));
+        }
+
+        Paragraph::new(format!("> {}\u{258F}", self.input))
+            .block(Block::default().borders(Borders::ALL).title(Line::from(title)))
+            .style(Style::default().fg(Color::White).bg(Color::Black))
+    }
+}
+
+/// Bottom three rows of `area`, where prompts are drawn over a pane.
+pub fn prompt_area(area: Rect) -> Rect {
+    let height = area.height.min(3);
+    Rect {
+        y: area.y + area.height - height,
+        height,
+        ..area
+    }
+}
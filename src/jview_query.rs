@@ -0,0 +1,574 @@
+use std::fmt;
+use regex::Regex;
+use crate::jview_entry::JournalEntry;
+use crate::jview_filter::{LogFilter, SearchMode};
+use crate::jview_text;
+
+/// Comparison used by a `field<op>value` term.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Op {
+    Eq,    // =
+    Ne,    // !=
+    Lt,    // <
+    Le,    // <=
+    Gt,    // >
+    Ge,    // >=
+    Match, // ~ (regex)
+}
+
+impl Op {
+    fn symbol(&self) -> &'static str {
+        match self {
+            Op::Eq => "=",
+            Op::Ne => "!=",
+            Op::Lt => "<",
+            Op::Le => "<=",
+            Op::Gt => ">",
+            Op::Ge => ">=",
+            Op::Match => "~",
+        }
+    }
+}
+
+/// Filter AST built from the search box.
+#[derive(Debug, Clone)]
+pub enum Expr {
+    And(Vec<Expr>),
+    Or(Vec<Expr>),
+    Not(Box<Expr>),
+    Field {
+        name: String,
+        op: Op,
+        value: String,
+        pattern: Option<Regex>, // Compiled value for `~`
+    },
+    Text(LogFilter),
+}
+
+/// Why a query could not be parsed, with the character offset it was detected at.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub position: usize,
+    pub message: String,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} at column {}", self.message, self.position + 1)
+    }
+}
+
+/// A parsed search query such as `priority<=err unit=nginx _PID=1234 !"healthcheck"`.
+///
+/// Terms separated by spaces must all match, `or` (or `|`) separates alternatives,
+/// `!` negates the following term and parentheses group terms. `field<op>value`
+/// terms compare journal fields, anything else is matched as text against the
+/// rendered log line.
+#[derive(Debug, Clone, Default)]
+pub struct Query {
+    expr: Option<Expr>,
+}
+
+/// Syslog priority names in the order of their numeric value.
+pub const PRIORITY_NAMES: [&str; 8] = ["emerg", "alert", "crit", "err", "warning", "notice", "info", "debug"];
+
+/// Resolves a priority given by name or number to its numeric value.
+pub fn parse_priority(value: &str) -> Option<u8> {
+    let value = value.to_lowercase();
+    let aliases = [("panic", 0), ("error", 3), ("warn", 4)];
+
+    if let Ok(n) = value.parse::<u8>() {
+        return (n <= 7).then_some(n);
+    }
+    if let Some(n) = PRIORITY_NAMES.iter().position(|p| *p == value) {
+        return Some(n as u8);
+    }
+    aliases.iter().find(|(name, _)| *name == value).map(|(_, n)| *n)
+}
+
+/// Maps the short field names accepted in queries to journal field names.
+fn field_name(name: &str) -> String {
+    match name.to_lowercase().as_str() {
+        "priority" | "prio" | "p" => "PRIORITY".to_string(),
+        "unit" | "u" => "_SYSTEMD_UNIT".to_string(),
+        "pid" => "_PID".to_string(),
+        "uid" => "_UID".to_string(),
+        "comm" => "_COMM".to_string(),
+        "exe" => "_EXE".to_string(),
+        "host" | "hostname" => "_HOSTNAME".to_string(),
+        "ident" | "identifier" | "tag" => "SYSLOG_IDENTIFIER".to_string(),
+        "boot" => "_BOOT_ID".to_string(),
+        "msg" | "message" => "MESSAGE".to_string(),
+        _ => name.to_uppercase(),
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Token {
+    LParen,
+    RParen,
+    Not,
+    Or,
+    Word { text: String, quoted: bool },
+}
+
+fn tokenize(input: &str) -> Result<Vec<(usize, Token)>, ParseError> {
+    let chars: Vec<char> = input.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        let start = i;
+        match c {
+            _ if c.is_whitespace() => {
+                i += 1;
+            }
+            '(' => {
+                tokens.push((start, Token::LParen));
+                i += 1;
+            }
+            ')' => {
+                tokens.push((start, Token::RParen));
+                i += 1;
+            }
+            '!' => {
+                tokens.push((start, Token::Not));
+                i += 1;
+            }
+            _ => {
+                // A word runs until whitespace or a parenthesis; quoted parts may contain either
+                let mut text = String::new();
+                let mut quoted = false;
+                while i < chars.len() && !chars[i].is_whitespace() && chars[i] != '(' && chars[i] != ')' {
+                    if chars[i] == '"' {
+                        let quote_start = i;
+                        i += 1;
+                        while i < chars.len() && chars[i] != '"' {
+                            text.push(chars[i]);
+                            i += 1;
+                        }
+                        if i == chars.len() {
+                            return Err(ParseError {
+                                position: quote_start,
+                                message: "unterminated quote".to_string(),
+                            });
+                        }
+                        quoted = true;
+                    } else {
+                        text.push(chars[i]);
+                    }
+                    i += 1;
+                }
+                let is_or = !quoted && (text.eq_ignore_ascii_case("or") || text == "|");
+                if is_or {
+                    tokens.push((start, Token::Or));
+                } else {
+                    tokens.push((start, Token::Word { text, quoted: quoted && chars[start] == '"' }));
+                }
+            }
+        }
+    }
+
+    Ok(tokens)
+}
+
+struct Parser {
+    tokens: Vec<(usize, Token)>,
+    pos: usize,
+    end: usize, // Offset reported for errors at the end of the input
+    mode: SearchMode,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos).map(|(_, t)| t)
+    }
+
+    fn offset(&self) -> usize {
+        self.tokens.get(self.pos).map(|(o, _)| *o).unwrap_or(self.end)
+    }
+
+    fn error(&self, message: &str) -> ParseError {
+        ParseError {
+            position: self.offset(),
+            message: message.to_string(),
+        }
+    }
+
+    // or_expr := and_expr ("or" and_expr)*
+    fn parse_or(&mut self) -> Result<Expr, ParseError> {
+        let mut alternatives = vec![self.parse_and()?];
+        while self.peek() == Some(&Token::Or) {
+            self.pos += 1;
+            alternatives.push(self.parse_and()?);
+        }
+        Ok(if alternatives.len() == 1 {
+            alternatives.remove(0)
+        } else {
+            Expr::Or(alternatives)
+        })
+    }
+
+    // and_expr := unary+
+    fn parse_and(&mut self) -> Result<Expr, ParseError> {
+        let mut terms = Vec::new();
+        while let Some(token) = self.peek() {
+            if *token == Token::Or || *token == Token::RParen {
+                break;
+            }
+            terms.push(self.parse_unary()?);
+        }
+        match terms.len() {
+            0 => Err(self.error("expected a search term")),
+            1 => Ok(terms.remove(0)),
+            _ => Ok(Expr::And(terms)),
+        }
+    }
+
+    // unary := "!" unary | "(" or_expr ")" | term
+    fn parse_unary(&mut self) -> Result<Expr, ParseError> {
+        let offset = self.offset();
+        match self.tokens.get(self.pos).map(|(_, t)| t.clone()) {
+            Some(Token::Not) => {
+                self.pos += 1;
+                Ok(Expr::Not(Box::new(self.parse_unary()?)))
+            }
+            Some(Token::LParen) => {
+                self.pos += 1;
+                let inner = self.parse_or()?;
+                if self.peek() != Some(&Token::RParen) {
+                    return Err(ParseError {
+                        position: offset,
+                        message: "unclosed '('".to_string(),
+                    });
+                }
+                self.pos += 1;
+                Ok(inner)
+            }
+            Some(Token::Word { text, quoted }) => {
+                self.pos += 1;
+                self.parse_term(offset, &text, quoted)
+            }
+            _ => Err(self.error("expected a search term")),
+        }
+    }
+
+    fn parse_term(&self, offset: usize, text: &str, quoted: bool) -> Result<Expr, ParseError> {
+        if !quoted {
+            if let Some(field) = self.parse_field(offset, text)? {
+                return Ok(field);
+            }
+        }
+
+        LogFilter::new(text, self.mode)
+            .map(Expr::Text)
+            .map_err(|e| ParseError {
+                position: offset,
+                message: e.to_string().lines().last().unwrap_or("invalid regex").to_string(),
+            })
+    }
+
+    /// Parses `name<op>value`, or returns `None` when `text` is not a field term.
+    fn parse_field(&self, offset: usize, text: &str) -> Result<Option<Expr>, ParseError> {
+        let name_len = text
+            .char_indices()
+            .find(|(_, c)| !(c.is_ascii_alphanumeric() || *c == '_'))
+            .map(|(i, _)| i)
+            .unwrap_or(text.len());
+        if name_len == 0 || text.starts_with(|c: char| c.is_ascii_digit()) {
+            return Ok(None);
+        }
+
+        let rest = &text[name_len..];
+        let ops = [("!=", Op::Ne), ("<=", Op::Le), (">=", Op::Ge), ("=", Op::Eq), ("<", Op::Lt), (">", Op::Gt), ("~", Op::Match)];
+        let Some((symbol, op)) = ops.iter().find(|(symbol, _)| rest.starts_with(symbol)) else {
+            return Ok(None);
+        };
+
+        let name = field_name(&text[..name_len]);
+        let mut value = rest[symbol.len()..].to_string();
+        let value_offset = offset + name_len + symbol.len();
+        if value.is_empty() {
+            return Err(ParseError {
+                position: value_offset,
+                message: format!("missing value after '{}{}'", &text[..name_len], op.symbol()),
+            });
+        }
+
+        if name == "PRIORITY" && *op != Op::Match {
+            let Some(n) = parse_priority(&value) else {
+                return Err(ParseError {
+                    position: value_offset,
+                    message: format!("unknown priority '{}'", value),
+                });
+            };
+            value = n.to_string();
+        }
+        if name == "_SYSTEMD_UNIT" && matches!(op, Op::Eq | Op::Ne) && !value.contains('.') {
+            value += ".service";
+        }
+
+        let pattern = if *op == Op::Match {
+            let compiled = Regex::new(&value).map_err(|e| ParseError {
+                position: value_offset,
+                message: e.to_string().lines().last().unwrap_or("invalid regex").to_string(),
+            })?;
+            Some(compiled)
+        } else {
+            None
+        };
+
+        Ok(Some(Expr::Field { name, op: *op, value, pattern }))
+    }
+}
+
+fn compare(actual: &str, op: Op, value: &str) -> bool {
+    let ordering = match (actual.parse::<i64>(), value.parse::<i64>()) {
+        (Ok(a), Ok(b)) => a.cmp(&b),
+        _ => actual.cmp(value),
+    };
+    match op {
+        Op::Eq => ordering.is_eq(),
+        Op::Ne => ordering.is_ne(),
+        Op::Lt => ordering.is_lt(),
+        Op::Le => ordering.is_le(),
+        Op::Gt => ordering.is_gt(),
+        Op::Ge => ordering.is_ge(),
+        Op::Match => false, // Handled by the compiled pattern
+    }
+}
+
+impl Expr {
+    fn matches(&self, entry: &JournalEntry) -> bool {
+        match self {
+            Expr::And(terms) => terms.iter().all(|t| t.matches(entry)),
+            Expr::Or(terms) => terms.iter().any(|t| t.matches(entry)),
+            Expr::Not(term) => !term.matches(entry),
+            Expr::Field { name, op, value, pattern } => match (entry.get(name), pattern) {
+                (Some(actual), Some(pattern)) => pattern.is_match(actual),
+                (Some(actual), None) => compare(actual, *op, value),
+                (None, _) => *op == Op::Ne,
+            },
+            Expr::Text(filter) => filter.matches(&entry.to_line()),
+        }
+    }
+
+    fn collect_ranges(&self, line: &str, ranges: &mut Vec<(usize, usize)>) {
+        match self {
+            Expr::And(terms) | Expr::Or(terms) => {
+                for term in terms {
+                    term.collect_ranges(line, ranges);
+                }
+            }
+            Expr::Text(filter) => ranges.extend(filter.find_ranges(line)),
+            Expr::Not(_) | Expr::Field { .. } => {} // Nothing to point at
+        }
+    }
+}
+
+impl Query {
+    /// Parses the text typed into the search box.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - The query text, empty to match everything.
+    /// * `mode` - Whether free-text terms are substrings or regular expressions.
+    ///
+    /// # Returns
+    ///
+    /// The query, or the first syntax error found.
+    pub fn parse(input: &str, mode: SearchMode) -> Result<Query, ParseError> {
+        let tokens = tokenize(input)?;
+        if tokens.is_empty() {
+            return Ok(Query::default());
+        }
+
+        let mut parser = Parser {
+            tokens,
+            pos: 0,
+            end: input.chars().count(),
+            mode,
+        };
+        let expr = parser.parse_or()?;
+        if parser.pos < parser.tokens.len() {
+            return Err(parser.error("unexpected ')'"));
+        }
+        Ok(Query { expr: Some(expr) })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.expr.is_none()
+    }
+
+    pub fn matches(&self, entry: &JournalEntry) -> bool {
+        match &self.expr {
+            Some(expr) => expr.matches(entry),
+            None => true,
+        }
+    }
+
+    /// Byte ranges of `line` matched by free-text terms, sorted and non-overlapping.
+    pub fn highlight_ranges(&self, line: &str) -> Vec<(usize, usize)> {
+        let mut ranges = Vec::new();
+        if let Some(expr) = &self.expr {
+            expr.collect_ranges(line, &mut ranges);
+        }
+        jview_text::merge_ranges(ranges)
+    }
+
+    /// journalctl arguments that narrow the source down before the query is
+    /// evaluated in-process.
+    ///
+    /// Only terms every matching entry must satisfy are passed down: positive
+    /// `FIELD=value` terms become journal matches and priority bounds become a
+    /// `--priority` range. Everything is still evaluated in-process afterwards.
+    pub fn journal_matches(&self) -> Vec<String> {
+        let terms: Vec<&Expr> = match &self.expr {
+            Some(Expr::And(terms)) => terms.iter().collect(),
+            Some(expr) => vec![expr],
+            None => Vec::new(),
+        };
+        let mut matches = Vec::new();
+        let (mut lowest, mut highest) = (0u8, 7u8);
+
+        for term in terms {
+            let Expr::Field { name, op, value, .. } = term else {
+                continue;
+            };
+            if name == "PRIORITY" {
+                let Ok(n) = value.parse::<u8>() else { continue };
+                match op {
+                    Op::Eq => (lowest, highest) = (lowest.max(n), highest.min(n)),
+                    Op::Le => highest = highest.min(n),
+                    // `<emerg` selects nothing, emerg alone is the narrowest range to pass
+                    Op::Lt => highest = highest.min(n.saturating_sub(1)),
+                    Op::Ge => lowest = lowest.max(n),
+                    Op::Gt => lowest = lowest.max(n + 1),
+                    Op::Ne | Op::Match => {}
+                }
+            } else if *op == Op::Eq {
+                matches.push(format!("{}={}", name, value));
+            }
+        }
+
+        if (lowest, highest) != (0, 7) && lowest <= highest {
+            matches.push(format!("--priority={}..{}", lowest, highest));
+        }
+        matches
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::jview_entry::fixtures::entry;
+
+    /// The parsed query as an s-expression, free-text terms shown as `text`.
+    fn shape(input: &str) -> String {
+        fn render(expr: &Expr) -> String {
+            let list = |kind: &str, terms: &[Expr]| {
+                let terms: Vec<String> = terms.iter().map(render).collect();
+                format!("({} {})", kind, terms.join(" "))
+            };
+            match expr {
+                Expr::And(terms) => list("and", terms),
+                Expr::Or(terms) => list("or", terms),
+                Expr::Not(term) => format!("(not {})", render(term)),
+                Expr::Field { name, op, value, .. } => format!("{}{}{}", name, op.symbol(), value),
+                Expr::Text(_) => "text".to_string(),
+            }
+        }
+        let query = Query::parse(input, SearchMode::Text).unwrap();
+        query.expr.as_ref().map(render).unwrap_or_default()
+    }
+
+    fn error_column(input: &str) -> usize {
+        Query::parse(input, SearchMode::Text).unwrap_err().position + 1
+    }
+
+    fn matches(input: &str) -> Vec<String> {
+        Query::parse(input, SearchMode::Text).unwrap().journal_matches()
+    }
+
+    #[test]
+    fn and_binds_tighter_than_or() {
+        assert_eq!(shape("a=1 b=2 or c=3"), "(or (and A=1 B=2) C=3)");
+        assert_eq!(shape("a=1 | b=2 c=3"), "(or A=1 (and B=2 C=3))");
+    }
+
+    #[test]
+    fn not_applies_to_the_next_term_only() {
+        assert_eq!(shape("!a=1 b=2"), "(and (not A=1) B=2)");
+        assert_eq!(shape("!!a=1"), "(not (not A=1))");
+        assert_eq!(shape("!(a=1 or b=2) c=3"), "(and (not (or A=1 B=2)) C=3)");
+    }
+
+    #[test]
+    fn parentheses_group_alternatives() {
+        assert_eq!(shape("a=1 (b=2 or c=3)"), "(and A=1 (or B=2 C=3))");
+        assert_eq!(shape("((a=1))"), "A=1");
+    }
+
+    #[test]
+    fn short_field_names_map_to_journal_fields() {
+        assert_eq!(shape("priority<=err"), "PRIORITY<=3");
+        assert_eq!(shape("unit=nginx pid!=1"), "(and _SYSTEMD_UNIT=nginx.service _PID!=1)");
+        assert_eq!(shape("unit=cron.timer"), "_SYSTEMD_UNIT=cron.timer");
+    }
+
+    #[test]
+    fn quoted_words_are_text() {
+        assert_eq!(shape("\"a=1\""), "text");
+        assert_eq!(shape("\"or\""), "text");
+        assert_eq!(shape("\"two words\" or x"), "(or text text)");
+
+        let query = Query::parse("\"disk (sda) full\"", SearchMode::Text).unwrap();
+        assert!(query.matches(&entry(&[("MESSAGE", "disk (sda) full")])));
+        assert!(!query.matches(&entry(&[("MESSAGE", "disk full")])));
+    }
+
+    #[test]
+    fn errors_point_at_the_offending_column() {
+        assert_eq!(error_column("a=1 \"open"), 5);
+        assert_eq!(error_column("(a=1 b=2"), 1);
+        assert_eq!(error_column("a=1 )"), 5);
+        assert_eq!(error_column("a=1 or"), 7);
+        assert_eq!(error_column("x priority=loud"), 12);
+        assert_eq!(error_column("pid="), 5);
+    }
+
+    #[test]
+    fn field_terms_compare_numbers_numerically() {
+        let query = Query::parse("pid>=100 !unit=cron", SearchMode::Text).unwrap();
+        assert!(query.matches(&entry(&[("_PID", "1000"), ("_SYSTEMD_UNIT", "nginx.service")])));
+        assert!(!query.matches(&entry(&[("_PID", "99"), ("_SYSTEMD_UNIT", "nginx.service")])));
+        assert!(!query.matches(&entry(&[("_PID", "1000"), ("_SYSTEMD_UNIT", "cron.service")])));
+    }
+
+    #[test]
+    fn only_required_equality_terms_are_passed_down() {
+        assert_eq!(matches("unit=nginx pid=12 timeout"), ["_SYSTEMD_UNIT=nginx.service", "_PID=12"]);
+        assert!(matches("unit=nginx or unit=cron").is_empty());
+        assert!(matches("pid!=12 comm~ssh").is_empty());
+    }
+
+    #[test]
+    fn priority_bounds_fold_into_one_range() {
+        assert_eq!(matches("priority<=warning"), ["--priority=0..4"]);
+        assert_eq!(matches("priority<warning"), ["--priority=0..3"]);
+        assert_eq!(matches("priority>=err"), ["--priority=3..7"]);
+        assert_eq!(matches("priority>err"), ["--priority=4..7"]);
+        assert_eq!(matches("priority=notice"), ["--priority=5..5"]);
+        assert_eq!(matches("priority>=crit priority<notice"), ["--priority=2..4"]);
+        assert!(matches("priority<=debug").is_empty());
+        assert!(matches("priority>notice priority<err").is_empty());
+    }
+
+    #[test]
+    fn priority_below_emerg_selects_nothing() {
+        let query = Query::parse("priority<emerg", SearchMode::Text).unwrap();
+        assert_eq!(query.journal_matches(), ["--priority=0..0"]);
+        assert!(!query.matches(&entry(&[("PRIORITY", "0")])));
+    }
+}
@@ -1,11 +1,19 @@
-use crossterm::event::KeyCode;
+use std::sync::mpsc::{self, Receiver};
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+use crossterm::event::{self, KeyCode};
 use ratatui::widgets::Paragraph;
 use ratatui::{
     widgets::{List},
 };
+use crate::jview_config::settings;
+use crate::jview_error::{self, SourceError};
 use crate::jview_logs;
 use crate::jview_search;
 use crate::jview_selector;
+use crate::jview_source::{LogSource, UnitAction, UnitInfo};
+use crate::jview_status;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum UiSection {
@@ -23,26 +31,37 @@ impl UiSection {
             UiSection::Search => UiSection::Logs,
             UiSection::Logs => UiSection::Selector,
             UiSection::Selector => UiSection::Search,
-            &UiSection::Help => todo!(),
+            UiSection::Help => UiSection::Logs, // Never selected, leave it for the logs
         }
     }
 }
 
-#[derive(Clone)]
+/// How long to wait for a key before redrawing, so followed logs keep scrolling.
+const INPUT_POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+/// A unit action running on its own thread, with the result it sends back.
+type PendingAction = (UnitAction, UnitInfo, Receiver<jview_error::Result<()>>);
+
 pub struct UiScreen {
+    source: Arc<dyn LogSource>,
     selected: UiSection,
     search_tui: jview_search::JviewSearch,
     logs_tui: jview_logs::JviewLogs,
     selector_tui: jview_selector::JviewSelector,
+    status_tui: jview_status::JviewStatus,
+    pending_action: Option<PendingAction>,
 }
 
 impl UiScreen {
-    pub fn new() -> Self {
+    pub fn new(source: Arc<dyn LogSource>) -> Self {
         UiScreen {
+            source: source.clone(),
             selected: UiSection::Logs,
             search_tui: jview_search::JviewSearch::new(),
-            logs_tui: jview_logs::JviewLogs::new(),
-            selector_tui: jview_selector::JviewSelector::new(),
+            logs_tui: jview_logs::JviewLogs::new(source.clone()),
+            selector_tui: jview_selector::JviewSelector::new(source.as_ref()),
+            status_tui: jview_status::JviewStatus::new(source.clone()),
+            pending_action: None,
         }
     }
 
@@ -59,23 +78,163 @@ impl UiScreen {
     }
 
     pub fn get_logs_widget<'a>(&self, selected: bool) -> List<'a> {
-        self.logs_tui.clone().get_logs_widget(selected)
+        self.logs_tui.get_logs_widget(selected)
     }
 
-    pub fn get_selector_widget(&self, selected: bool) -> List {
+    pub fn get_selector_widget(&self, selected: bool) -> List<'_> {
         self.selector_tui.clone().get_selector_widget(selected)
     }
 
+    pub fn get_unit_filter_widget(&self, selected: bool) -> Paragraph<'static> {
+        self.selector_tui.get_filter_widget(selected)
+    }
+
+    /// Whether there is a status panel, which needs a source with unit states.
+    pub fn has_unit_status(&self) -> bool {
+        self.status_tui.is_available()
+    }
+
+    pub fn get_unit_status_widget(&self, selected: bool) -> Paragraph<'static> {
+        self.status_tui.get_status_widget(selected)
+    }
+
+    /// Fills the search box with a query given on the command line.
+    pub fn set_search_query(&mut self, query: &str) {
+        self.search_tui.set_query(query);
+    }
+
+    pub fn start_following(&mut self) {
+        self.logs_tui.set_following();
+    }
+
+    pub fn toggle_badges(&mut self) {
+        self.logs_tui.toggle_badges();
+    }
+
+    /// Brings the log buffer up to date before the next frame is drawn.
+    pub fn refresh_logs(&mut self) {
+        self.logs_tui.refresh();
+    }
+
+    /// Reads the status of the highlighted unit again when it is due.
+    pub fn refresh_unit_status(&mut self) {
+        self.status_tui.refresh(self.selector_tui.highlighted_unit());
+    }
+
+    /// Starts an action confirmed in the selector on its own thread, as systemctl
+    /// may take a while to start or stop a unit. `finish_unit_action` picks up the
+    /// result.
+    fn run_unit_action(&mut self, action: UnitAction, unit: &UnitInfo) {
+        if let Some((running, other, _)) = &self.pending_action {
+            let text = format!("Still {} {}", running.running().to_lowercase(), other.name);
+            settings::set_message(&text, true);
+            return;
+        }
+
+        let (sender, receiver) = mpsc::channel();
+        let source = self.source.clone();
+        let (name, user) = (unit.name.clone(), settings::is_user_scope());
+        thread::spawn(move || {
+            let _ = sender.send(source.unit_action(&name, user, action));
+        });
+        settings::set_message(&format!("{} {}...", action.running(), unit.name), false);
+        self.pending_action = Some((action, unit.clone(), receiver));
+    }
+
+    /// Reports the action started by `run_unit_action` once it is done, then
+    /// follows the logs of the unit to see how it went. Failures are reported in
+    /// the help bar.
+    pub fn finish_unit_action(&mut self) {
+        let Some((action, unit, receiver)) = &self.pending_action else { return };
+        let result = match receiver.try_recv() {
+            Ok(result) => result,
+            Err(mpsc::TryRecvError::Empty) => return,
+            Err(mpsc::TryRecvError::Disconnected) => Err(SourceError::CommandFailed {
+                program: "systemctl".to_string(),
+                message: "stopped without a result".to_string(),
+            }),
+        };
+        let (action, unit) = (*action, unit.clone());
+        self.pending_action = None;
+
+        match result {
+            Ok(()) => {
+                settings::set_message(&format!("{} {}", action.past(), unit.name), false);
+                settings::set_unit(unit.short_name());
+                self.logs_tui.follow_latest();
+                self.selected = UiSection::Logs;
+            }
+            Err(e) => settings::set_message(&format!("Could not {} {}: {}", action.verb(), unit.name, e.describe()), true),
+        }
+        self.selector_tui.reload(self.source.as_ref());
+        self.status_tui.invalidate();
+    }
+
+    /// Switches between the system units and the user's own units. The units
+    /// picked in one scope mean nothing in the other one, so all are shown again.
+    fn toggle_scope(&mut self) {
+        let user = !settings::is_user_scope();
+        settings::set_user_scope(user);
+        settings::clear_units();
+        settings::set_message(if user { "Showing the user's own units" } else { "Showing the system units" }, false);
+        self.selector_tui.reload(self.source.as_ref());
+        self.status_tui.invalidate();
+    }
+
+    pub fn get_error_widget(&self) -> Option<Paragraph<'static>> {
+        self.logs_tui.get_error_widget()
+    }
+
+    pub fn get_confirm_widget(&self) -> Option<Paragraph<'static>> {
+        self.selector_tui.get_confirm_widget()
+    }
+
+    pub fn get_detail_widget(&self) -> Option<List<'static>> {
+        self.logs_tui.get_detail_widget()
+    }
+
+    pub fn set_detail_max_height(&mut self, h: usize) {
+        self.logs_tui.set_detail_max_height(h);
+    }
+
+    pub fn get_boots_widget(&self) -> Option<List<'static>> {
+        self.logs_tui.get_boots_widget()
+    }
+
+    pub fn set_boots_max_height(&mut self, h: usize) {
+        self.logs_tui.set_boots_max_height(h);
+    }
+
+    pub fn get_time_range_widget(&self) -> Option<List<'static>> {
+        self.logs_tui.get_time_range_widget()
+    }
+
+    pub fn get_picker_widget(&self) -> Option<List<'static>> {
+        self.search_tui.get_picker_widget()
+    }
+
+    pub fn get_prompt_widget(&self) -> Option<Paragraph<'static>> {
+        self.logs_tui.get_prompt_widget().or_else(|| self.selector_tui.get_prompt_widget())
+    }
+
     pub fn set_logs_max_height(&mut self, h: usize) {
         self.logs_tui.set_max_height(h);
     }
 
+    pub fn set_logs_max_width(&mut self, w: usize) {
+        self.logs_tui.set_max_width(w);
+    }
+
     pub fn set_selector_max_height(&mut self, h: usize) {
         self.selector_tui.set_max_height(h);
     }
 }
 
 pub fn screen_navigate(screen: &mut UiScreen) -> Result<bool, std::io::Error> {
+    if !event::poll(INPUT_POLL_INTERVAL)? {
+        return Ok(false); // No input, redraw with whatever arrived in the meantime
+    }
+
     if screen.get_selected() == UiSection::Search {
         let res = screen.search_tui.get_search_input()?;
         match res {
@@ -99,6 +258,14 @@ pub fn screen_navigate(screen: &mut UiScreen) -> Result<bool, std::io::Error> {
                 screen.next_section();
                 return Ok(false);
             }
+            KeyCode::Char(key @ ('/' | '?')) => {
+                // Type the pattern in the search box, Enter or Esc comes back here
+                screen.logs_tui.begin_find(key == '?');
+                screen.search_tui.start_find(key == '?');
+                screen.selected = UiSection::Search;
+                return Ok(false);
+            }
+            KeyCode::Char('u') => screen.toggle_scope(),
             _ => {}
         }
         return Ok(false);
@@ -106,7 +273,11 @@ pub fn screen_navigate(screen: &mut UiScreen) -> Result<bool, std::io::Error> {
 
     if screen.get_selected() == UiSection::Selector {
         let res = screen.selector_tui.navigate()?;
+        if let Some((action, unit)) = screen.selector_tui.take_action() {
+            screen.run_unit_action(action, &unit);
+        }
         match res {
+            KeyCode::Char('u') => screen.toggle_scope(),
             KeyCode::Char('q') => return Ok(true),
             KeyCode::Char('Q') => return Ok(true),
             KeyCode::Tab => {
@@ -1,15 +1,47 @@
 use ratatui::{
     style::{Style, Color},
-    widgets::{Block, Borders, Paragraph},
+    text::{Line, Span},
+    widgets::{Block, Borders, List, ListItem, Paragraph},
 };
-use crossterm::event::{self, KeyCode};
+use crossterm::event::{self, KeyCode, KeyModifiers};
 use ratatui::style;
+use crate::jview_config::settings;
+use crate::jview_filter::{LogFilter, SearchMode};
+use crate::jview_history::{SavedQueries, SearchHistory};
+use crate::jview_prompt::{JviewPrompt, PromptAction};
+use crate::jview_query::Query;
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct JviewSearch {
     input: String,
     help: String,
     inited: bool,
+    mode: SearchMode,
+    error: Option<String>, // Why the current input can't be used as a filter
+    find: Option<Find>,    // Less-style search typed after `/` or `?`, while editing
+    history: SearchHistory,
+    history_pos: Option<usize>, // History entry shown while going through it with Up/Down
+    draft: String,              // Input from before going through the history
+    reverse: Option<ReverseSearch>,
+    saved: SavedQueries,
+    picker: Option<usize>,       // Selected saved query, while the picker is open
+    naming: Option<JviewPrompt>, // Name to save the input under, while asked for
+}
+
+/// Ctrl-R search back through the history, like in a shell.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct ReverseSearch {
+    needle: String,
+    found: Option<usize>, // History entry containing the needle
+}
+
+/// A pattern being typed to jump between matches, instead of filtering.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Find {
+    input: String,
+    backward: bool,   // Started with `?`
+    mode: SearchMode,
+    previous: (String, SearchMode), // Pattern to go back to on Esc or an empty Enter
 }
 
 impl JviewSearch {
@@ -18,6 +50,127 @@ impl JviewSearch {
             input: "".to_string(),
             help: "Type to start searching...".to_string(),
             inited: false,
+            mode: SearchMode::Text,
+            error: None,
+            find: None,
+            history: SearchHistory::load(),
+            history_pos: None,
+            draft: String::new(),
+            reverse: None,
+            saved: SavedQueries::load(),
+            picker: None,
+            naming: None,
+        }
+    }
+
+    /// Switches the box to typing a pattern to find, like `/` and `?` in less.
+    ///
+    /// # Arguments
+    ///
+    /// * `backward` - Whether matches are searched towards older lines first.
+    pub fn start_find(&mut self, backward: bool) {
+        self.error = None;
+        self.find = Some(Find {
+            input: String::new(),
+            backward,
+            mode: self.mode,
+            previous: settings::get_find(),
+        });
+    }
+
+    /// Publishes the pattern being typed so the logs pane jumps to it while typing.
+    fn apply_find(&mut self) {
+        let Some(find) = &self.find else { return };
+        match LogFilter::new(&find.input, find.mode) {
+            Ok(_) => {
+                self.error = None;
+                settings::set_find(&find.input, find.mode);
+            }
+            Err(e) => self.error = Some(e.to_string()),
+        }
+    }
+
+    /// Leaves find mode, keeping the typed pattern or going back to the previous one.
+    fn end_find(&mut self, keep: bool) {
+        if let Some(find) = self.find.take() {
+            if !keep || find.input.is_empty() || self.error.is_some() {
+                settings::set_find(&find.previous.0, find.previous.1);
+            }
+        }
+        self.error = None;
+    }
+
+    /// Shows a query that was already handed to the logs pane through the settings.
+    pub fn set_query(&mut self, query: &str) {
+        self.input = query.to_string();
+        self.inited = !query.is_empty();
+        self.apply();
+    }
+
+    /// Validates the input and hands it to the logs pane as the active filter.
+    fn apply(&mut self) {
+        match Query::parse(&self.input, self.mode) {
+            Ok(query) => {
+                self.error = None;
+                settings::set_search(&self.input, self.mode);
+
+                // Matches already passed to journalctl must not hide entries the edited query wants
+                let wanted = query.journal_matches();
+                if !settings::get_matches().iter().all(|m| wanted.contains(m)) {
+                    settings::set_matches(wanted);
+                }
+            }
+            Err(e) => {
+                // Keep filtering with the last valid query while the user is still typing
+                self.error = Some(e.to_string());
+            }
+        }
+    }
+
+    /// Passes the field matches of the submitted query down to journalctl.
+    ///
+    /// This reloads the logs pane, so it only happens on Enter and not per keystroke.
+    fn submit(&mut self) {
+        if let Ok(query) = Query::parse(&self.input, self.mode) {
+            settings::set_matches(query.journal_matches());
+            self.history.push(&self.input, self.mode);
+        }
+        self.history_pos = None;
+    }
+
+    /// Replaces the input with a query from the history or the saved queries.
+    fn recall(&mut self, query: &str, mode: SearchMode) {
+        self.input = query.to_string();
+        self.mode = mode;
+        self.inited = true;
+        self.apply();
+    }
+
+    /// Steps through the history with Up (`older`) and Down, back to what was
+    /// being typed past the newest entry.
+    fn browse_history(&mut self, older: bool) {
+        let len = self.history.entries().len();
+        let pos = match (self.history_pos, older) {
+            (None, true) if len > 0 => {
+                self.draft = self.input.clone();
+                Some(len - 1)
+            }
+            (Some(pos), true) => Some(pos.saturating_sub(1)),
+            (Some(pos), false) if pos + 1 < len => Some(pos + 1),
+            (Some(_), false) => None,
+            (None, _) => return,
+        };
+
+        self.history_pos = pos;
+        match pos {
+            Some(pos) => {
+                let (query, mode) = self.history.entries()[pos].clone();
+                self.recall(&query, mode);
+            }
+            None => {
+                let draft = std::mem::take(&mut self.draft);
+                self.recall(&draft, self.mode);
+            }
         }
     }
 }
@@ -45,32 +198,139 @@ impl JviewSearch {
     ///
     /// A `Paragraph` widget configured for the search functionality.
     pub fn get_search_widget(self, selected: bool) -> Paragraph<'static> {
-        let intext;
-        if self.inited == true {
-            intext = format!("\u{1F50D} {}", self.input);
+        if let Some(prompt) = &self.naming {
+            return prompt.get_prompt_widget();
+        }
+        if let Some(reverse) = &self.reverse {
+            let found = reverse.found.map_or("", |i| self.history.entries()[i].0.as_str());
+            return Paragraph::new(format!("(reverse-i-search)`{}': {}", reverse.needle, found))
+                .block(Block::default().borders(Borders::ALL).title("History [^R]: Older | [Enter]: Use | [Esc]: Cancel"))
+                .style(get_style(selected));
+        }
+        if let Some(find) = &self.find {
+            let (key, direction) = if find.backward { ('?', "backward") } else { ('/', "forward") };
+            let mut title = vec![Span::raw(format!(
+                "Find {} ({}) [Enter]: Done | [Esc]: Cancel | [n/N]: Next/Previous",
+                direction,
+                find.mode.name()
+            ))];
+            if let Some(e) = &self.error {
+                title.push(Span::styled(format!(" {} ", e), Style::default().fg(Color::White).bg(Color::Red)));
+            }
+            return Paragraph::new(format!("{}{}", key, find.input))
+                .block(Block::default().borders(Borders::ALL).title(Line::from(title)))
+                .style(get_style(selected));
+        }
+
+        let intext = if self.inited {
+            format!("\u{1F50D} {}", self.input)
         } else {
-            intext = format!("\u{1F50D} {}", self.help);
+            format!("\u{1F50D} {}", self.help)
+        };
+        let mut title = vec![Span::raw(format!(
+            "Search ({}) [^T: toggle] [Up/Down/^R]: History [^S]: Save [^O]: Saved",
+            self.mode.name()
+        ))];
+        if let Some(e) = &self.error {
+            title.push(Span::styled(format!(" {} ", e), Style::default().fg(Color::White).bg(Color::Red)));
         }
         Paragraph::new(intext)
-            .block(Block::default().borders(Borders::ALL).title("Search"))
+            .block(Block::default().borders(Borders::ALL).title(Line::from(title)))
             .style(get_style(selected))
     }
 
+    /// The picker of saved queries, while it is open.
+    pub fn get_picker_widget(&self) -> Option<List<'static>> {
+        let selected_idx = self.picker?;
+        let items: Vec<ListItem> = if self.saved.list().is_empty() {
+            vec![ListItem::new("No saved queries yet, save one with Ctrl-S in the search box")]
+        } else {
+            self.saved
+                .list()
+                .iter()
+                .enumerate()
+                .map(|(i, q)| {
+                    let line = Line::from(vec![
+                        Span::styled(format!("{:<20} ", q.name), Style::default().fg(Color::Cyan)),
+                        Span::raw(q.query.clone()),
+                    ]);
+                    if i == selected_idx {
+                        ListItem::new(line).style(Style::default().fg(Color::Black).bg(Color::Cyan))
+                    } else {
+                        ListItem::new(line)
+                    }
+                })
+                .collect()
+        };
+
+        Some(
+            List::new(items)
+                .block(Block::default().borders(Borders::ALL).title("Saved queries [Enter]: Use | [Del]: Delete | [Esc]: Close"))
+                .style(Style::default().fg(Color::White).bg(Color::Black)),
+        )
+    }
+
     pub fn get_search_input(&mut self) -> Result<KeyCode, std::io::Error> {
         let mut input = String::new();
         if let event::Event::Key(key) = event::read()? {
+            if self.find.is_some() {
+                return Ok(self.find_input(key));
+            }
+            if self.naming.is_some() {
+                self.name_input(key);
+                return Ok(KeyCode::Enter);
+            }
+            if self.reverse.is_some() {
+                self.reverse_input(key);
+                return Ok(KeyCode::Enter);
+            }
+            if self.picker.is_some() {
+                return Ok(self.picker_input(key));
+            }
+
+            let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
             match key.code {
+                KeyCode::Up => {
+                    self.browse_history(true);
+                    return Ok(KeyCode::Enter);
+                }
+                KeyCode::Down => {
+                    self.browse_history(false);
+                    return Ok(KeyCode::Enter);
+                }
+                KeyCode::Char('r') if ctrl => {
+                    self.reverse = Some(ReverseSearch {
+                        needle: String::new(),
+                        found: None,
+                    });
+                    return Ok(KeyCode::Enter);
+                }
+                KeyCode::Char('s') if ctrl => {
+                    if !self.input.trim().is_empty() {
+                        self.naming = Some(JviewPrompt::new(&format!("Save '{}' as", self.input)));
+                    }
+                    return Ok(KeyCode::Enter);
+                }
+                KeyCode::Char('o') if ctrl => {
+                    self.picker = Some(0);
+                    return Ok(KeyCode::Enter);
+                }
                 KeyCode::Esc => {
                     return Ok(KeyCode::Tab); // Escape to stop input
                 }
                 KeyCode::Backspace => {
                     self.input.pop(); // Remove last character
                 }
+                KeyCode::Char('t') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                    self.mode = self.mode.toggle();
+                }
                 KeyCode::Enter => {
+                    self.submit();
                     return Ok(KeyCode::Tab); // Enter to submit input
                 }
                 KeyCode::Char(c) => {
                     self.inited = true;
+                    self.history_pos = None;
                     input.push(c); // Add character to input string
                 }
                 KeyCode::Tab => {
@@ -80,6 +340,110 @@ impl JviewSearch {
             }
         }
         self.input += &input.clone();
+        self.apply();
         Ok(KeyCode::Enter)
     }
+
+    /// Edits the pattern to find; Enter and Esc hand control back to the logs pane.
+    fn find_input(&mut self, key: event::KeyEvent) -> KeyCode {
+        let Some(find) = self.find.as_mut() else {
+            return KeyCode::Tab;
+        };
+        match key.code {
+            KeyCode::Esc => {
+                self.end_find(false);
+                return KeyCode::Tab;
+            }
+            KeyCode::Enter => {
+                self.end_find(true);
+                return KeyCode::Tab;
+            }
+            KeyCode::Char('t') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                find.mode = find.mode.toggle();
+            }
+            KeyCode::Backspace => {
+                find.input.pop();
+            }
+            KeyCode::Char(c) => find.input.push(c),
+            _ => {}
+        }
+        self.apply_find();
+        KeyCode::Enter
+    }
+
+    /// Types the text to look for after Ctrl-R; Ctrl-R again finds an older entry.
+    fn reverse_input(&mut self, key: event::KeyEvent) {
+        let Some(reverse) = self.reverse.as_mut() else { return };
+        let len = self.history.entries().len();
+        match key.code {
+            KeyCode::Esc => self.reverse = None,
+            KeyCode::Enter => {
+                if let Some(i) = reverse.found {
+                    let (query, mode) = self.history.entries()[i].clone();
+                    self.recall(&query, mode);
+                }
+                self.reverse = None;
+            }
+            KeyCode::Char('r') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                let before = reverse.found.unwrap_or(len);
+                if let Some(i) = self.history.search(&reverse.needle, before) {
+                    reverse.found = Some(i);
+                }
+            }
+            KeyCode::Backspace => {
+                reverse.needle.pop();
+                reverse.found = self.history.search(&reverse.needle, len);
+            }
+            KeyCode::Char(c) => {
+                reverse.needle.push(c);
+                reverse.found = self.history.search(&reverse.needle, len);
+            }
+            _ => {}
+        }
+    }
+
+    /// Asks for the name to save the input under after Ctrl-S.
+    fn name_input(&mut self, key: event::KeyEvent) {
+        let Some(prompt) = self.naming.as_mut() else { return };
+        match prompt.handle_key(key) {
+            PromptAction::Edit => {}
+            PromptAction::Submit => {
+                let name = prompt.input().trim().to_string();
+                if name.is_empty() {
+                    prompt.set_error("Name is empty");
+                } else {
+                    self.saved.save(&name, &self.input, self.mode);
+                    self.naming = None;
+                }
+            }
+            PromptAction::Cancel => self.naming = None,
+        }
+    }
+
+    /// Picks a saved query after Ctrl-O; using one submits it right away.
+    fn picker_input(&mut self, key: event::KeyEvent) -> KeyCode {
+        let Some(selected_idx) = self.picker else {
+            return KeyCode::Enter;
+        };
+        let count = self.saved.list().len();
+        match key.code {
+            KeyCode::Esc => self.picker = None,
+            KeyCode::Up => self.picker = Some(selected_idx.saturating_sub(1)),
+            KeyCode::Down if selected_idx + 1 < count => self.picker = Some(selected_idx + 1),
+            KeyCode::Delete => {
+                self.saved.remove(selected_idx);
+                self.picker = Some(selected_idx.min(count.saturating_sub(2)));
+            }
+            KeyCode::Enter => {
+                self.picker = None;
+                if let Some(saved) = self.saved.list().get(selected_idx).cloned() {
+                    self.recall(&saved.query, saved.mode);
+                    self.submit();
+                    return KeyCode::Tab; // Back to the logs pane to see the result
+                }
+            }
+            _ => {}
+        }
+        KeyCode::Enter
+    }
 }
@@ -1,13 +1,29 @@
 use ratatui::{
-    style::{Style, Color},
-    widgets::{Block, Borders, List, ListItem},
+    style::{Style, Color, Modifier},
+    text::{Line, Span},
+    widgets::{Block, Borders, List, ListItem, Paragraph, Wrap},
 };
 use crossterm::event::{self, Event, KeyCode};
-use std::process::Command;
 #[allow(unused_imports)]
 use crate::jview_config;
 use crate::jview_config::settings;
 use crate::jview_debug;
+use crate::jview_prompt::{JviewPrompt, PromptAction};
+use crate::jview_source::{LogSource, UnitAction, UnitInfo};
+use crate::jview_text;
+use crate::jview_theme;
+
+/// Placeholder row when the source has no units to list.
+const ALL_UNITS: &str = "<All Systemd units>";
+
+/// Unit types the list can be narrowed to with `v`, in order, with their label.
+const KINDS: [(&str, Option<&str>); 5] = [
+    ("services", Some("service")),
+    ("sockets", Some("socket")),
+    ("timers", Some("timer")),
+    ("mounts", Some("mount")),
+    ("units", None),
+];
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct JviewSelector {
@@ -15,43 +31,186 @@ pub struct JviewSelector {
     vertical_start: usize,
     horizontal_start: usize,
     max_viewer_height: usize,
-    units: Vec<String>,
+    units: Vec<UnitInfo>,
+    kind_idx: usize,                // Entry of KINDS the list is narrowed to
+    by_state: bool,                 // Failed units first, then the other states, instead of by name
+    filter: String,                 // Text typed after `/` to fuzzy-match unit names
+    filtering: bool,                // Keys go to the filter
+    visible: Vec<(usize, Vec<usize>)>, // Indexes into `units` of the listed units, in order, with the byte offsets matching the filter
+    confirm: Option<(UnitAction, UnitInfo)>, // Action waiting for y/n
+    pending: Option<(UnitAction, UnitInfo)>, // Confirmed action, for the screen to run
+    namespace: Option<JviewPrompt>,          // Journal namespace being typed
 }
 
 impl JviewSelector {
-    pub fn new() -> Self {
-        JviewSelector {
+    pub fn new(source: &dyn LogSource) -> Self {
+        let mut selector = JviewSelector {
+            units: fetch_units(source),
             selected_idx: 0,
             vertical_start: 0,
             horizontal_start: 0,
             max_viewer_height: 15,
-            units: fetch_systemd_units(),
+            kind_idx: 0,
+            by_state: false,
+            filter: String::new(),
+            filtering: false,
+            visible: Vec::new(),
+            confirm: None,
+            pending: None,
+            namespace: None,
+        };
+        selector.apply_filter();
+
+        // Start on the first unit picked on the command line, if any
+        let chosen = settings::get_units();
+        if let Some(pos) = selector.visible.iter().position(|(i, _)| chosen.iter().any(|c| c == selector.units[*i].short_name())) {
+            selector.selected_idx = pos;
+            selector.vertical_start = pos.saturating_sub(10);
         }
+        selector
     }
 
     pub fn set_max_height(&mut self, h: usize) {
         self.max_viewer_height = h;
     }
 
-    fn get_visible_units(&self) -> Vec<String> {
-        let mut vitems: Vec<String> = Vec::new(); // Viewable units
+    /// Number of units that fit inside the borders of the list.
+    fn visible_rows(&self) -> usize {
+        self.max_viewer_height.saturating_sub(2).max(1)
+    }
+
+    /// Scrolls just enough to keep the cursor row on screen.
+    fn scroll_to_selected(&mut self) {
+        if self.selected_idx < self.vertical_start {
+            self.vertical_start = self.selected_idx;
+        } else if self.selected_idx >= self.vertical_start + self.visible_rows() {
+            self.vertical_start = self.selected_idx + 1 - self.visible_rows();
+        }
+    }
+
+    /// The name of the unit under the cursor, as it goes into the settings.
+    fn selected_unit(&self) -> Option<&str> {
+        self.visible.get(self.selected_idx).map(|(i, _)| self.units[*i].short_name())
+    }
+
+    /// Lists the units again, e.g. after one was started or stopped, keeping the
+    /// cursor on the same unit.
+    pub fn reload(&mut self, source: &dyn LogSource) {
+        let current = self.highlighted_unit().map(|u| u.to_string());
+        self.units = fetch_units(source);
+        self.apply_filter();
+        if let Some(pos) = current.and_then(|c| self.visible.iter().position(|(i, _)| self.units[*i].name == c)) {
+            self.selected_idx = pos;
+            self.scroll_to_selected();
+        }
+    }
+
+    /// The action confirmed by the user, once.
+    pub fn take_action(&mut self) -> Option<(UnitAction, UnitInfo)> {
+        self.pending.take()
+    }
+
+    /// Asks to confirm an action on the unit under the cursor.
+    fn ask_action(&mut self, action: UnitAction) {
+        if settings::is_read_only() {
+            settings::set_message("Read-only mode, units cannot be started or stopped", true);
+            return;
+        }
+        if let Some(&(i, _)) = self.visible.get(self.selected_idx) {
+            if self.units[i].name != ALL_UNITS {
+                self.confirm = Some((action, self.units[i].clone()));
+            }
+        }
+    }
+
+    /// Full name of the unit under the cursor, `None` on the placeholder.
+    pub fn highlighted_unit(&self) -> Option<&str> {
+        self.visible
+            .get(self.selected_idx)
+            .map(|(i, _)| self.units[*i].name.as_str())
+            .filter(|name| *name != ALL_UNITS)
+    }
+
+    /// Lists the units of the chosen type that match the filter again, and puts
+    /// the cursor on the first one. Matches are ranked best first, the other
+    /// units are in the chosen order.
+    fn apply_filter(&mut self) {
+        let kind = KINDS[self.kind_idx].1;
+        let mut ranked: Vec<(i64, usize, Vec<usize>)> = self
+            .units
+            .iter()
+            .enumerate()
+            .filter(|(_, unit)| kind.is_none_or(|k| unit.kind() == k) || unit.name == ALL_UNITS)
+            .filter_map(|(i, unit)| {
+                if self.filter.is_empty() {
+                    Some((0, i, Vec::new()))
+                } else {
+                    jview_text::fuzzy_match(&self.filter, unit.short_name()).map(|(score, pos)| (score, i, pos))
+                }
+            })
+            .collect();
 
-        for (i, line) in self.units.iter().enumerate() {
-            if i < self.vertical_start {
-                continue; // Skip lines until the vertical offset
+        let units = &self.units;
+        if self.filter.is_empty() && self.by_state {
+            ranked.sort_by_key(|(_, i, _)| (state_rank(&units[*i].active), *i));
+        } else if !self.filter.is_empty() {
+            // Shorter names first among equal scores, they are the closer match
+            ranked.sort_by_key(|(score, i, _)| (-score, units[*i].name.len(), *i));
+        }
+        self.visible = ranked.into_iter().map(|(_, i, pos)| (i, pos)).collect();
+        self.selected_idx = 0;
+        self.vertical_start = 0;
+    }
+
+    /// Types into the filter after `/`.
+    ///
+    /// # Returns
+    ///
+    /// `true` when the key was taken by the filter.
+    fn filter_input(&mut self, key: KeyCode) -> bool {
+        match key {
+            KeyCode::Esc => {
+                self.filtering = false;
+                self.filter.clear();
             }
+            KeyCode::Backspace => {
+                self.filter.pop();
+            }
+            KeyCode::Char(c) if c != ' ' => self.filter.push(c),
+            _ => return false, // Moving, picking and Tab work while filtering
+        }
+        self.apply_filter();
+        true
+    }
 
-            if vitems.len() >= self.max_viewer_height {
+    fn get_visible_units(&self) -> Vec<(usize, Line<'static>)> {
+        let mut vitems: Vec<(usize, Line<'static>)> = Vec::new(); // Viewable units, by index into `units`
+
+        for (i, positions) in self.visible.iter().skip(self.vertical_start) {
+            if vitems.len() >= self.visible_rows() {
                 break; // Stop if we've taken enough lines to fit the section
             }
 
-            let visible_line = if line.len() > self.horizontal_start {
-                &line[self.horizontal_start..]
-            } else {
-                ""
-            };
+            let line = self.units[*i].short_name();
+            let skipped = jview_text::skip_columns(line, self.horizontal_start);
 
-            vitems.push(visible_line.to_string());
+            // Matched characters stand out
+            let matched = Style::default().fg(Color::LightRed).add_modifier(Modifier::BOLD | Modifier::UNDERLINED);
+            let name = match self.units[*i].active.as_str() {
+                "failed" => Style::default().fg(Color::LightRed),
+                _ => Style::default(),
+            };
+            let spans: Vec<Span> = line[skipped..]
+                .char_indices()
+                .map(|(offset, c)| {
+                    if positions.contains(&(offset + skipped)) {
+                        Span::styled(c.to_string(), matched)
+                    } else {
+                        Span::styled(c.to_string(), name)
+                    }
+                })
+                .collect();
+            vitems.push((*i, Line::from(spans)));
         }
 
         vitems
@@ -65,40 +224,95 @@ impl JviewSelector {
     pub fn navigate(&mut self) -> Result<KeyCode, std::io::Error> {
 
         if let Event::Key(key) = event::read()? {
+            if let Some(confirm) = self.confirm.take() {
+                match key.code {
+                    KeyCode::Char('y') | KeyCode::Char('Y') | KeyCode::Enter => self.pending = Some(confirm),
+                    KeyCode::Char('n') | KeyCode::Char('N') | KeyCode::Esc => {}
+                    _ => self.confirm = Some(confirm), // Keep asking
+                }
+                return Ok(KeyCode::Enter);
+            }
+            if let Some(mut prompt) = self.namespace.take() {
+                match prompt.handle_key(key) {
+                    PromptAction::Edit => self.namespace = Some(prompt),
+                    PromptAction::Submit => {
+                        let namespace = prompt.input().trim();
+                        settings::set_namespace((!namespace.is_empty()).then_some(namespace));
+                    }
+                    PromptAction::Cancel => {}
+                }
+                return Ok(KeyCode::Enter);
+            }
+            if self.filtering && self.filter_input(key.code) {
+                return Ok(KeyCode::Enter);
+            }
+            let Some(unit) = self.selected_unit().map(|u| u.to_string()) else {
+                // Nothing matches the filter, only leaving or editing it makes sense
+                return Ok(match key.code {
+                    KeyCode::Tab => KeyCode::Tab,
+                    KeyCode::Char('/') => {
+                        self.filtering = true;
+                        KeyCode::Enter
+                    }
+                    _ => KeyCode::Enter,
+                });
+            };
+
             match key.code {
+                KeyCode::Char('/') => self.filtering = true,
+                KeyCode::Esc if !self.filter.is_empty() => {
+                    self.filter.clear();
+                    self.apply_filter();
+                }
+                KeyCode::Char('s') => self.ask_action(UnitAction::Start),
+                KeyCode::Char('x') => self.ask_action(UnitAction::Stop),
+                KeyCode::Char('r') => self.ask_action(UnitAction::Restart),
+                KeyCode::Char('l') => self.ask_action(UnitAction::Reload),
+                KeyCode::Char('u') => return Ok(KeyCode::Char('u')),
+                KeyCode::Char('n') => {
+                    let label = "Journal namespace (empty for the default, * for all, +name for it and the default)";
+                    let mut prompt = JviewPrompt::new(label);
+                    prompt.set_input(&settings::get_namespace().unwrap_or_default());
+                    self.namespace = Some(prompt);
+                }
+                KeyCode::Char('v') => {
+                    self.kind_idx = (self.kind_idx + 1) % KINDS.len();
+                    self.apply_filter();
+                }
+                KeyCode::Char('o') => {
+                    self.by_state = !self.by_state;
+                    self.apply_filter();
+                }
                 KeyCode::Char('q') => return Ok(KeyCode::Char('q')),
                 KeyCode::Char('Q') => return Ok(KeyCode::Char('q')),
                 KeyCode::Tab => {
                     return Ok(KeyCode::Tab);
                 }
+                KeyCode::Char(' ') | KeyCode::Enter if unit == ALL_UNITS => {
+                    settings::clear_units();
+                }
+                KeyCode::Char(' ') => {
+                    settings::toggle_unit(&unit);
+                    jview_debug::log_debug_info("Toggled Unit to filter:", format_args!("{}", unit));
+                }
+                KeyCode::Char('a') => settings::clear_units(),
                 KeyCode::Enter => {
-                    settings::set_unit(&self.units[self.selected_idx]);
+                    settings::set_unit(&unit);
+                    self.filtering = false;
                     jview_debug::log_debug_info("Selected Unit ID to filter: ", format_args!("{}", self.selected_idx));
-                    jview_debug::log_debug_info("Selected Unit to filter:", format_args!("{}", self.units[self.selected_idx]));
+                    jview_debug::log_debug_info("Selected Unit to filter:", format_args!("{}", unit));
                     return Ok(KeyCode::Tab);
                 }
-                KeyCode::Up => {
-                    if self.vertical_start > 0 {
-                        self.vertical_start -= 1;
-                    }
-                    if self.selected_idx > 0 {
-                        self.selected_idx -= 1;
-                    }
-                    jview_debug::log_debug_info("Clearing unit 1", format_args!("{:?}", key.code));
-                    settings::clear_unit();
+                KeyCode::Up if self.selected_idx > 0 => {
+                    self.selected_idx -= 1;
+                    self.scroll_to_selected();
                 }
-                KeyCode::Down => {
+                KeyCode::Down if self.selected_idx + 1 < self.visible.len() => {
                     self.selected_idx += 1;
-                    if self.selected_idx >= self.max_viewer_height-4 {
-                        self.vertical_start += 1;
-                    }
-                    jview_debug::log_debug_info("Clearing unit 2", format_args!("{:?}", key.code));
-                    settings::clear_unit();
+                    self.scroll_to_selected();
                 }
-                KeyCode::Left => {
-                    if self.horizontal_start > 0 {
-                        self.horizontal_start -= 1;
-                    }
+                KeyCode::Left if self.horizontal_start > 0 => {
+                    self.horizontal_start -= 1;
                 }
                 KeyCode::Right => {
                     self.horizontal_start += 1;
@@ -122,24 +336,28 @@ impl JviewSelector {
     /// A `List` widget configured for the systemd units.
     pub fn get_selector_widget(&self, selected: bool) -> List<'static> {
         let vunits = self.get_visible_units();
+        let chosen = settings::get_units();
         let items: Vec<ListItem> = vunits
             .into_iter()
             .enumerate()
-            .map(|(i, unit)| {
+            .map(|(i, (unit_idx, unit))| {
                 let style = if (i + self.vertical_start) == self.selected_idx {
                     Style::default().fg(Color::Black).bg(Color::Cyan)
                 } else {
                     get_style(selected)
                 };
-                if settings::get_unit() == unit {
-                    let su = format!("\u{2714} {}", unit);
-                    ListItem::new(su).style(style)
+                let info = &self.units[unit_idx];
+                let mut spans = unit.spans;
+                spans.insert(0, state_badge(&info.active));
+                if let Some(n) = chosen.iter().position(|u| u == info.short_name()) {
+                    // Same color as the tag of its lines in the logs pane
+                    spans.insert(0, Span::styled("\u{2714} ", Style::default().fg(jview_theme::unit_color(n))));
                 } else if (i+self.vertical_start) == self.selected_idx {
-                    let su = format!("\u{2713} {}", unit);
-                    ListItem::new(su).style(style)
+                    spans.insert(0, Span::raw("\u{2713} "));
                 } else {
-                    ListItem::new(unit).style(style)
+                    spans.insert(0, Span::raw("  "));
                 }
+                ListItem::new(Line::from(spans)).style(style)
             })
             .collect();
 
@@ -147,37 +365,102 @@ impl JviewSelector {
             .block(
                 Block::default()
                     .borders(Borders::ALL)
-                    .title("Systemd Units"),
+                    .title(format!(
+                        "{} {} by {}",
+                        if settings::is_user_scope() { "User" } else { "System" },
+                        KINDS[self.kind_idx].0,
+                        if self.by_state { "state" } else { "name" }
+                    )),
+            )
+            .style(get_style(selected))
+    }
+
+    /// Creates the filter box shown above the unit list.
+    ///
+    /// # Arguments
+    ///
+    /// * `selected` - Is the selector currently selected?
+    ///
+    /// # Returns
+    ///
+    /// A `Paragraph` widget with the filter and how many units match it.
+    pub fn get_filter_widget(&self, selected: bool) -> Paragraph<'static> {
+        let text = if self.filtering || !self.filter.is_empty() {
+            let cursor = if self.filtering { "\u{258F}" } else { "" };
+            format!("/{}{}", self.filter, cursor)
+        } else {
+            "Press / to filter".to_string()
+        };
+
+        Paragraph::new(text)
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .title(format!("Filter: {} of {}", self.visible.len(), self.units.len())),
             )
             .style(get_style(selected))
     }
+
+    /// The namespace prompt, while it is open.
+    pub fn get_prompt_widget(&self) -> Option<Paragraph<'static>> {
+        self.namespace.as_ref().map(|p| p.get_prompt_widget())
+    }
+
+    /// Creates the confirmation dialog of an action.
+    ///
+    /// # Returns
+    ///
+    /// A `Paragraph` widget asking whether to go ahead, `None` when nothing is asked.
+    pub fn get_confirm_widget(&self) -> Option<Paragraph<'static>> {
+        let (action, unit) = self.confirm.as_ref()?;
+        let verb = action.verb();
+        let question = format!("{}{} {}?", verb[..1].to_uppercase(), &verb[1..], unit.name);
+
+        Some(
+            Paragraph::new(vec![Line::from(question), Line::from(""), Line::from("[y]: Yes | [n]: No")])
+                .wrap(Wrap { trim: true })
+                .block(Block::default().borders(Borders::ALL).title("Confirm"))
+                .style(Style::default().fg(Color::White).bg(Color::Black)),
+        )
+    }
 }
 
-fn fetch_systemd_units() -> Vec<String> {
-    let output = Command::new("bash")
-        .args(["-c", "systemctl list-units --all --no-pager --plain | awk '{print $1}'|grep '.service'"])
-        .output()
-        .expect("Failed to run systemctl command");
-    /*
-        Use this in the future and use json output
-        systemctl list-units --all --plain --no-legend --no-pager --output=json
-    */
-    if output.status.success() {
-        let mut result = Vec::new();
-
-        for line in String::from_utf8_lossy(&output.stdout).lines() {
-            if line.contains(".service") {
-                result.push(line.replace(".service", ""));
-            } else {
-                result.push(line.to_string())
-            }
+/// Units offered by the source, or a placeholder when it has none.
+fn fetch_units(source: &dyn LogSource) -> Vec<UnitInfo> {
+    match source.list_units(settings::is_user_scope()) {
+        Ok(units) if !units.is_empty() => units,
+        Ok(_) => vec![UnitInfo::named(ALL_UNITS)],
+        Err(e) => {
+            jview_debug::log_debug_info("Failed to list units: ", format_args!("{}", e));
+            settings::set_message(&format!("Could not list units: {}", e.describe()), true);
+            vec![UnitInfo::named(ALL_UNITS)]
         }
-        result
-    } else {
-        vec!["<All Systemd units>".to_string()]
     }
 }
 
+/// Where units in a state go when the list is ordered by state: the ones that
+/// need a look first.
+fn state_rank(active: &str) -> u8 {
+    match active {
+        "failed" => 0,
+        "activating" | "deactivating" | "reloading" | "refreshing" => 1,
+        "active" => 2,
+        "inactive" => 3,
+        _ => 4,
+    }
+}
+
+/// A dot in the color of the unit state, hollow when the unit is not running.
+/// Units whose state is unknown get a blank instead.
+fn state_badge(active: &str) -> Span<'static> {
+    let dot = match active {
+        "" => " ",
+        "inactive" => "\u{25CB}",
+        _ => "\u{25CF}",
+    };
+    Span::styled(format!("{} ", dot), Style::default().fg(jview_theme::unit_state_color(active)))
+}
+
 fn get_style(selected: bool) -> Style {
     if selected {
         Style::default().fg(Color::Cyan).bg(Color::Black)
@@ -0,0 +1,677 @@
+use std::collections::HashMap;
+use std::env;
+use std::fmt;
+use std::sync::atomic::AtomicBool;
+use std::sync::mpsc::{self, Sender};
+use std::sync::{Arc, Mutex};
+use crate::jview_entry::JournalEntry;
+use crate::jview_error::{Result, SourceError};
+use crate::jview_follow::LogFollower;
+use crate::jview_journal_file::JournalFileSource;
+use crate::jview_journalctl::JournalctlSource;
+use crate::jview_query::parse_priority;
+use crate::jview_time;
+
+/// Most entries kept in memory while new ones keep arriving, by the logs pane
+/// and by sources that read the journal themselves. The oldest go first.
+pub const MAX_ENTRIES: usize = 100_000;
+
+/// Which entries the logs pane wants from a source.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LogQuery {
+    pub units: Vec<String>,           // Services to show, interleaved by time, empty for all
+    pub user: bool,                   // Look at the user's own units instead of the system's
+    pub namespace: Option<String>,    // Journal namespace, `*` for all, `+name` for it and the default one
+    pub since: Option<String>,        // Lower time bound, in journalctl syntax
+    pub until: Option<String>,        // Upper time bound, in journalctl syntax
+    pub boot: Option<String>,         // Boot ID, or offset like `0` or `-1`
+    pub priority: Option<(u8, u8)>,   // Most and least important priority to show
+    pub matches: Vec<String>,         // `FIELD=value` and `--priority=a..b` journalctl matches
+    pub after_cursor: Option<String>, // Only entries logged after this cursor
+}
+
+impl LogQuery {
+    /// Everything in the journal of the system or the user, and of a namespace.
+    pub fn scope(user: bool, namespace: Option<&str>) -> Self {
+        LogQuery {
+            user,
+            namespace: namespace.map(String::from),
+            ..Default::default()
+        }
+    }
+
+    /// The query as journalctl arguments, without the output format and cursor.
+    pub fn journalctl_args(&self) -> Vec<String> {
+        let mut jargs = vec![];
+
+        if self.user {
+            jargs.push("--user".to_string());
+        }
+        if let Some(namespace) = &self.namespace {
+            jargs.push(format!("--namespace={}", namespace));
+        }
+        // journalctl shows the entries of any of the units given with -u
+        for unit in &self.units {
+            jargs.push("-u".to_string());
+            jargs.push(unit.clone());
+        }
+        if let Some(since) = &self.since {
+            jargs.push(format!("--since={}", since));
+        }
+        if let Some(until) = &self.until {
+            jargs.push(format!("--until={}", until));
+        }
+        if let Some(boot) = &self.boot {
+            jargs.push(format!("--boot={}", boot));
+        }
+
+        // Field matches come last, journalctl takes them as positional arguments
+        jargs.extend(self.effective_matches());
+        jargs
+    }
+
+    /// The matches with the priority range folded in. journalctl only honours the
+    /// last `--priority`, so both ranges are merged into one.
+    fn effective_matches(&self) -> Vec<String> {
+        let Some((mut low, mut high)) = self.priority else {
+            return self.matches.clone();
+        };
+        let mut matches = Vec::new();
+
+        for m in &self.matches {
+            match m.strip_prefix("--priority=").map(priority_range) {
+                Some((l, h)) => {
+                    low = low.max(l);
+                    high = high.min(h);
+                }
+                None => matches.push(m.clone()),
+            }
+        }
+        matches.push(format!("--priority={}..{}", low, high));
+        matches
+    }
+
+    /// In-process equivalent of the journalctl arguments, for sources that read
+    /// entries themselves. The time bounds are resolved once, so relative ones
+    /// like `-1h` don't move while the entries are tested.
+    ///
+    /// # Arguments
+    ///
+    /// * `entries` - The whole journal, oldest first, to count boot offsets in.
+    ///
+    /// # Returns
+    ///
+    /// The matcher, or an error if a time bound is not a valid timestamp.
+    pub fn matcher(&self, entries: &[JournalEntry]) -> Result<EntryMatcher> {
+        let bound = |time: &Option<String>, unbounded| match time {
+            Some(text) => jview_time::parse_time(text)
+                .ok_or_else(|| SourceError::InvalidQuery(format!("Invalid time '{}'", text))),
+            None => Ok(unbounded),
+        };
+
+        // Boot offsets count the boots of the journal the entries are read from, like `list_boots`
+        let in_scope = |entry: &JournalEntry| {
+            self.namespace.as_deref().is_none_or(|ns| namespace_matches(entry, ns)) && unit_matches(entry, "", self.user)
+        };
+
+        Ok(EntryMatcher {
+            since: bound(&self.since, i64::MIN)?,
+            until: bound(&self.until, i64::MAX)?,
+            boot: self.boot.as_deref().and_then(|boot| resolve_boot(entries.iter().filter(|e| in_scope(e)), boot)),
+            namespace: self.namespace.clone(),
+            units: self.units.clone(),
+            user: self.user,
+            matches: self.effective_matches(),
+        })
+    }
+
+    /// The entries this query selects, including the cursor bound. Boot offsets
+    /// count all of `entries`.
+    pub fn select(&self, entries: &[JournalEntry]) -> Result<Vec<JournalEntry>> {
+        let matcher = self.matcher(entries)?;
+        Ok(self.select_with(&matcher, entries))
+    }
+
+    /// Like `select`, with a matcher made earlier, e.g. once for a follower.
+    /// A cursor that is not among `entries` selects nothing, as every entry
+    /// after it was handed out already or is gone.
+    pub fn select_with(&self, matcher: &EntryMatcher, entries: &[JournalEntry]) -> Vec<JournalEntry> {
+        let start = match &self.after_cursor {
+            // The cursor is usually one of the last entries, when sources are asked for new ones
+            Some(cursor) => match entries.iter().rposition(|e| e.cursor() == Some(cursor.as_str())) {
+                Some(pos) => pos + 1,
+                None => return Vec::new(),
+            },
+            None => 0,
+        };
+        entries[start..].iter().filter(|e| matcher.matches(e)).cloned().collect()
+    }
+}
+
+/// A `LogQuery` prepared for testing entries, see `LogQuery::matcher`.
+#[derive(Debug, Clone)]
+pub struct EntryMatcher {
+    since: i64,           // Lower time bound in microseconds since the epoch
+    until: i64,           // Upper time bound in microseconds since the epoch
+    boot: Option<String>, // Boot ID, offsets already resolved
+    namespace: Option<String>,
+    units: Vec<String>,
+    user: bool,
+    matches: Vec<String>, // With the priority range folded in
+}
+
+impl EntryMatcher {
+    pub fn matches(&self, entry: &JournalEntry) -> bool {
+        let realtime = entry.realtime_usec().unwrap_or(0) as i64;
+
+        realtime >= self.since
+            && realtime <= self.until
+            && self.boot.as_deref().is_none_or(|b| entry.get("_BOOT_ID") == Some(b))
+            && self.namespace.as_deref().is_none_or(|ns| namespace_matches(entry, ns))
+            && (self.units.is_empty() && unit_matches(entry, "", self.user)
+                || self.units.iter().any(|unit| unit_matches(entry, unit, self.user)))
+            && field_matches(entry, &self.matches)
+    }
+}
+
+/// Parses a `--priority` value: a single priority means it and everything more important.
+pub fn priority_range(value: &str) -> (u8, u8) {
+    match value.split_once("..") {
+        Some((low, high)) => (
+            parse_priority(low).unwrap_or(0),
+            parse_priority(high).unwrap_or(7),
+        ),
+        None => (0, parse_priority(value).unwrap_or(7)),
+    }
+}
+
+/// Boot IDs in the order the boots happened, given entries sorted by time.
+pub fn boot_ids<'a>(entries: impl IntoIterator<Item = &'a JournalEntry>) -> Vec<String> {
+    let mut boots: Vec<String> = Vec::new();
+    for boot in entries.into_iter().filter_map(|e| e.get("_BOOT_ID")) {
+        if !boots.iter().any(|b| b == boot) {
+            boots.push(boot.to_string());
+        }
+    }
+    boots
+}
+
+/// One boot in the journal, as listed by `journalctl --list-boots`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BootInfo {
+    pub index: i64,       // 0 for the newest boot, negative for the ones before it
+    pub id: String,       // Boot ID without dashes
+    pub first_usec: u64,  // Realtime of the first entry of the boot
+    pub last_usec: u64,   // Realtime of the last entry of the boot
+}
+
+/// The boots found in entries sorted by time, oldest first.
+pub fn boot_list(entries: &[JournalEntry]) -> Vec<BootInfo> {
+    let mut boots: Vec<BootInfo> = Vec::new();
+    for entry in entries {
+        let (Some(id), Some(usec)) = (entry.get("_BOOT_ID"), entry.realtime_usec()) else {
+            continue;
+        };
+        match boots.iter_mut().find(|b| b.id == id) {
+            Some(boot) => {
+                boot.first_usec = boot.first_usec.min(usec);
+                boot.last_usec = boot.last_usec.max(usec);
+            }
+            None => boots.push(BootInfo {
+                index: 0,
+                id: id.to_string(),
+                first_usec: usec,
+                last_usec: usec,
+            }),
+        }
+    }
+
+    let newest = boots.len() as i64 - 1;
+    for (i, boot) in boots.iter_mut().enumerate() {
+        boot.index = i as i64 - newest;
+    }
+    boots
+}
+
+/// Turns a `--boot` value into a boot ID like journalctl: `0` is the current
+/// boot, negative offsets count back from it and positive ones count from the
+/// first boot. Boot IDs may be given with dashes.
+fn resolve_boot<'a>(entries: impl IntoIterator<Item = &'a JournalEntry>, boot: &str) -> Option<String> {
+    let boots = boot_ids(entries);
+    let id = boot.replace('-', "");
+
+    if id.len() == 32 {
+        return Some(id.to_lowercase());
+    }
+    let offset: i64 = boot.parse().ok()?;
+    let index = if offset > 0 {
+        offset - 1
+    } else {
+        boots.len() as i64 - 1 + offset
+    };
+    // An unknown boot selects nothing rather than everything
+    let unknown = "0".repeat(32);
+    Some(usize::try_from(index).ok().and_then(|i| boots.get(i).cloned()).unwrap_or(unknown))
+}
+
+/// The first of `units` that `entry` belongs to, to tell apart the units shown
+/// together in the logs pane.
+pub fn entry_unit<'a>(entry: &JournalEntry, units: &'a [String], user: bool) -> Option<&'a str> {
+    units
+        .iter()
+        .find(|unit| unit_matches(entry, unit, user))
+        .map(|unit| unit.as_str())
+}
+
+/// Mirrors `journalctl -u` (or `--user -u`), which also picks up what systemd
+/// logged about the unit.
+fn unit_matches(entry: &JournalEntry, unit: &str, user: bool) -> bool {
+    if user && unit.is_empty() {
+        return entry.get("_SYSTEMD_USER_UNIT").is_some() || entry.get("_SYSTEMD_OWNER_UID").is_some();
+    }
+    if unit.is_empty() {
+        return true;
+    }
+
+    let service = format!("{}.service", unit);
+    let fields = if user {
+        ["_SYSTEMD_USER_UNIT", "USER_UNIT", "OBJECT_SYSTEMD_USER_UNIT", "COREDUMP_USER_UNIT"]
+    } else {
+        ["_SYSTEMD_UNIT", "UNIT", "OBJECT_SYSTEMD_UNIT", "COREDUMP_UNIT"]
+    };
+    fields
+        .iter()
+        .filter_map(|field| entry.get(field))
+        .any(|value| value == unit || value == service)
+}
+
+/// Mirrors `journalctl --namespace`. Entries of the default namespace have no
+/// `_NAMESPACE` field.
+fn namespace_matches(entry: &JournalEntry, namespace: &str) -> bool {
+    let own = entry.get("_NAMESPACE");
+    match namespace {
+        "*" => true,
+        _ => match namespace.strip_prefix('+') {
+            Some(name) => own.is_none() || own == Some(name),
+            None => own == Some(namespace),
+        },
+    }
+}
+
+/// Describes whose logs are read, for the pane titles: the system's or the
+/// user's, and the namespace when it is not the default one.
+pub fn scope_label(user: bool, namespace: Option<&str>) -> String {
+    let scope = if user { "user" } else { "system" };
+    match namespace {
+        Some(ns) => format!("{}, namespace {}", scope, ns),
+        None => scope.to_string(),
+    }
+}
+
+/// Mirrors journalctl match arguments: matches on the same field are alternatives,
+/// matches on different fields must all hold, `--priority=a..b` is a range.
+fn field_matches(entry: &JournalEntry, matches: &[String]) -> bool {
+    let mut fields: Vec<&str> = Vec::new();
+
+    for m in matches {
+        if let Some(range) = m.strip_prefix("--priority=") {
+            let (low, high) = priority_range(range);
+            if !entry.priority().is_some_and(|p| p >= low && p <= high) {
+                return false;
+            }
+        } else if let Some((field, _)) = m.split_once('=') {
+            if !fields.contains(&field) {
+                fields.push(field);
+            }
+        }
+    }
+
+    fields.iter().all(|field| {
+        matches
+            .iter()
+            .filter_map(|m| m.split_once('='))
+            .filter(|(f, _)| f == field)
+            .any(|(f, value)| entry.get(f) == Some(value))
+    })
+}
+
+/// A unit as listed by `systemctl list-units`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct UnitInfo {
+    pub name: String,        // Full unit name, like `nginx.service`
+    pub load: String,        // `loaded`, `not-found`, ... empty when unknown
+    pub active: String,      // `active`, `failed`, `inactive`, ... empty when unknown
+    pub sub: String,         // `running`, `exited`, `waiting`, ... empty when unknown
+    pub description: String,
+}
+
+impl UnitInfo {
+    /// A unit only known by name, e.g. from the journal.
+    pub fn named(name: &str) -> Self {
+        UnitInfo {
+            name: name.to_string(),
+            ..Default::default()
+        }
+    }
+
+    /// The name to show and to filter the logs with: services go without their
+    /// `.service` suffix, like `journalctl -u` accepts them.
+    pub fn short_name(&self) -> &str {
+        self.name.strip_suffix(".service").unwrap_or(&self.name)
+    }
+
+    /// The unit type, the part of the name after the last dot.
+    pub fn kind(&self) -> &str {
+        self.name.rsplit_once('.').map_or("", |(_, kind)| kind)
+    }
+}
+
+/// What can be done to a unit from the selector, like `systemctl <verb>`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UnitAction {
+    Start,
+    Stop,
+    Restart,
+    Reload,
+}
+
+impl UnitAction {
+    /// The systemctl command for the action.
+    pub fn verb(&self) -> &'static str {
+        match self {
+            UnitAction::Start => "start",
+            UnitAction::Stop => "stop",
+            UnitAction::Restart => "restart",
+            UnitAction::Reload => "reload",
+        }
+    }
+
+    /// The action under way, to show while systemctl runs.
+    pub fn running(&self) -> &'static str {
+        match self {
+            UnitAction::Start => "Starting",
+            UnitAction::Stop => "Stopping",
+            UnitAction::Restart => "Restarting",
+            UnitAction::Reload => "Reloading",
+        }
+    }
+
+    /// The action done, to report it.
+    pub fn past(&self) -> &'static str {
+        match self {
+            UnitAction::Start => "Started",
+            UnitAction::Stop => "Stopped",
+            UnitAction::Restart => "Restarted",
+            UnitAction::Reload => "Reloaded",
+        }
+    }
+}
+
+/// Units known only by name, sorted and without duplicates.
+pub fn named_units(names: impl IntoIterator<Item = String>) -> Vec<UnitInfo> {
+    let mut names: Vec<String> = names.into_iter().collect();
+    names.sort();
+    names.dedup();
+    names.iter().map(|name| UnitInfo::named(name)).collect()
+}
+
+/// Where journal entries and the list of units come from.
+///
+/// The UI only talks to this trait, so it runs the same on top of journalctl,
+/// journal files read natively, or entries held in memory.
+pub trait LogSource: fmt::Debug + Send + Sync {
+    /// Units that can be picked in the unit selector, the user's own units when
+    /// `user` is set. Sources that only read the journal know no unit states.
+    fn list_units(&self, user: bool) -> Result<Vec<UnitInfo>>;
+
+    /// Entries selected by `query`, oldest first.
+    fn query(&self, query: &LogQuery) -> Result<Vec<JournalEntry>>;
+
+    /// Live feed of entries selected by `query` that are logged from now on,
+    /// or after `query.after_cursor` when it is set.
+    fn follow(&self, query: &LogQuery) -> Result<LogFollower>;
+
+    /// Whether this is the journal of the running system, as opposed to one
+    /// opened from files or a dump.
+    fn is_live(&self) -> bool;
+
+    /// What the source was warned about while reading, like journalctl hinting
+    /// that only the user's own entries are visible. Each warning is returned once.
+    fn take_warning(&self) -> Option<String> {
+        None
+    }
+
+    /// Boots in the journal of the system or the user, and of a namespace, as
+    /// in `LogQuery`, oldest first. Sources that can't ask the journal directly
+    /// find them in all of their entries.
+    fn list_boots(&self, user: bool, namespace: Option<&str>) -> Result<Vec<BootInfo>> {
+        Ok(boot_list(&self.query(&LogQuery::scope(user, namespace))?))
+    }
+
+    /// The given `systemctl show` properties of a unit, by name. Only the
+    /// running system has them.
+    fn unit_status(&self, _unit: &str, _user: bool, _properties: &[&str]) -> Result<HashMap<String, String>> {
+        Err(SourceError::Unsupported("Unit status needs the running system".to_string()))
+    }
+
+    /// Starts, stops, restarts or reloads a unit of the running system.
+    fn unit_action(&self, _unit: &str, _user: bool, _action: UnitAction) -> Result<()> {
+        Err(SourceError::Unsupported("Units can only be managed on the running system".to_string()))
+    }
+}
+
+/// Entries held in memory, used for journals loaded from a dump and for fixtures.
+#[derive(Debug, Default)]
+pub struct MemorySource {
+    entries: Mutex<Vec<JournalEntry>>,
+    followers: Mutex<Vec<(EntryMatcher, Sender<Result<JournalEntry>>)>>,
+}
+
+impl MemorySource {
+    pub fn new(entries: Vec<JournalEntry>) -> Self {
+        MemorySource {
+            entries: Mutex::new(entries),
+            followers: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// Appends an entry and hands it to every follower whose query selects it.
+    #[cfg(test)]
+    pub fn push(&self, entry: JournalEntry) {
+        let mut followers = self.followers.lock().unwrap();
+        followers.retain(|(matcher, sender)| !matcher.matches(&entry) || sender.send(Ok(entry.clone())).is_ok());
+        self.entries.lock().unwrap().push(entry);
+    }
+}
+
+impl LogSource for MemorySource {
+    fn list_units(&self, user: bool) -> Result<Vec<UnitInfo>> {
+        let field = if user { "_SYSTEMD_USER_UNIT" } else { "_SYSTEMD_UNIT" };
+        let entries = self.entries.lock().unwrap();
+        Ok(named_units(entries.iter().filter_map(|e| e.get(field)).map(|u| u.to_string())))
+    }
+
+    fn query(&self, query: &LogQuery) -> Result<Vec<JournalEntry>> {
+        query.select(&self.entries.lock().unwrap())
+    }
+
+    fn follow(&self, query: &LogQuery) -> Result<LogFollower> {
+        let (sender, receiver) = mpsc::channel();
+        let matcher = query.matcher(&self.entries.lock().unwrap())?;
+
+        // Catch up on what was pushed after the cursor, then receive new pushes
+        if query.after_cursor.is_some() {
+            for entry in self.query(query)? {
+                let _ = sender.send(Ok(entry));
+            }
+        }
+        self.followers.lock().unwrap().push((matcher, sender));
+        Ok(LogFollower::new(receiver, Arc::new(AtomicBool::new(false))))
+    }
+
+    fn is_live(&self) -> bool {
+        false
+    }
+}
+
+fn command_exists(name: &str) -> bool {
+    env::var_os("PATH")
+        .map(|paths| env::split_paths(&paths).any(|dir| dir.join(name).is_file()))
+        .unwrap_or(false)
+}
+
+/// journalctl when it is installed, otherwise the journal files on this machine.
+pub fn default_source() -> Arc<dyn LogSource> {
+    if command_exists("journalctl") {
+        Arc::new(JournalctlSource::new())
+    } else {
+        Arc::new(JournalFileSource::system())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::jview_entry::fixtures::numbered;
+
+    /// Entry `n` of the fixture journal, logged by `unit`.
+    fn entry(n: u64, unit: &str) -> JournalEntry {
+        numbered(n, &[("_SYSTEMD_UNIT", &format!("{}.service", unit)), ("MESSAGE", &format!("message {}", n))])
+    }
+
+    const BOOTS: [&str; 3] = [
+        "11111111111111111111111111111111",
+        "22222222222222222222222222222222",
+        "33333333333333333333333333333333",
+    ];
+
+    /// Six entries, alternating between nginx and cron, two per boot, with
+    /// priority `n`.
+    fn journal() -> Vec<JournalEntry> {
+        (0..6)
+            .map(|n| {
+                let mut entry = entry(n, if n % 2 == 0 { "nginx" } else { "cron" });
+                entry.set("_BOOT_ID", BOOTS[n as usize / 2]);
+                entry.set("PRIORITY", &n.to_string());
+                entry
+            })
+            .collect()
+    }
+
+    fn cursors(entries: &[JournalEntry]) -> Vec<&str> {
+        entries.iter().filter_map(|e| e.cursor()).collect()
+    }
+
+    #[test]
+    fn select_picks_the_units_asked_for() {
+        let mut systemd = entry(6, "init");
+        systemd.set("UNIT", "nginx.service");
+        let mut entries = journal();
+        entries.push(systemd);
+
+        let nginx = LogQuery { units: vec!["nginx".to_string()], ..Default::default() };
+        assert_eq!(cursors(&nginx.select(&entries).unwrap()), ["c0", "c2", "c4", "c6"]);
+
+        let both = LogQuery { units: vec!["nginx".to_string(), "cron.service".to_string()], ..Default::default() };
+        assert_eq!(both.select(&entries).unwrap().len(), 7);
+    }
+
+    #[test]
+    fn select_resolves_boot_offsets() {
+        let boot = |b: &str| LogQuery { boot: Some(b.to_string()), ..Default::default() };
+
+        assert_eq!(cursors(&boot("0").select(&journal()).unwrap()), ["c4", "c5"]);
+        assert_eq!(cursors(&boot("-1").select(&journal()).unwrap()), ["c2", "c3"]);
+        assert_eq!(cursors(&boot("1").select(&journal()).unwrap()), ["c0", "c1"]);
+        assert!(boot("-3").select(&journal()).unwrap().is_empty());
+        assert_eq!(cursors(&boot("22222222-2222-2222-2222-222222222222").select(&journal()).unwrap()), ["c2", "c3"]);
+    }
+
+    #[test]
+    fn boot_offsets_count_the_boots_of_the_scope() {
+        // The user only logged in during the first two boots
+        let mut entries = journal();
+        for (n, boot) in [(6, 0), (7, 1)] {
+            let mut entry = numbered(n, &[("_SYSTEMD_USER_UNIT", "app.service"), ("_BOOT_ID", BOOTS[boot])]);
+            entry.set("MESSAGE", "user");
+            entries.push(entry);
+        }
+        let source = MemorySource::new(entries);
+        let user = |boot: &str| LogQuery { user: true, boot: Some(boot.to_string()), ..Default::default() };
+
+        assert_eq!(cursors(&source.query(&user("0")).unwrap()), ["c7"]);
+        assert_eq!(cursors(&source.query(&user("-1")).unwrap()), ["c6"]);
+        assert_eq!(source.list_boots(true, None).unwrap().len(), 2);
+        assert_eq!(source.list_boots(false, None).unwrap().len(), 3);
+    }
+
+    #[test]
+    fn select_intersects_priority_ranges() {
+        let query = LogQuery {
+            priority: Some((0, 3)),
+            matches: vec!["--priority=2..6".to_string()],
+            ..Default::default()
+        };
+        assert_eq!(cursors(&query.select(&journal()).unwrap()), ["c2", "c3"]);
+        assert_eq!(query.effective_matches(), ["--priority=2..3"]);
+    }
+
+    #[test]
+    fn select_starts_after_the_cursor() {
+        let after = |c: &str| LogQuery {
+            units: vec!["cron".to_string()],
+            after_cursor: Some(c.to_string()),
+            ..Default::default()
+        };
+
+        assert_eq!(cursors(&after("c3").select(&journal()).unwrap()), ["c5"]);
+        assert!(after("c5").select(&journal()).unwrap().is_empty());
+        // Entries after a cursor that is gone were either handed out or dropped
+        assert!(after("unknown").select(&journal()).unwrap().is_empty());
+    }
+
+    #[test]
+    fn select_resolves_time_bounds() {
+        let between = |since: &str, until: &str| LogQuery {
+            since: Some(since.to_string()),
+            until: Some(until.to_string()),
+            ..Default::default()
+        };
+
+        assert_eq!(cursors(&between("@1700000002", "@1700000003.5").select(&journal()).unwrap()), ["c2", "c3"]);
+        assert_eq!(between("-1h", "+1h").select(&journal()).unwrap().len(), 0);
+        assert_eq!(between("2000-01-01", "now").select(&journal()).unwrap().len(), 6);
+
+        let error = between("soon", "now").select(&journal()).unwrap_err();
+        assert_eq!(error, SourceError::InvalidQuery("Invalid time 'soon'".to_string()));
+    }
+
+    #[test]
+    fn followers_get_the_pushed_entries_they_select() {
+        let source = MemorySource::new(journal());
+        let query = LogQuery { units: vec!["nginx".to_string()], ..Default::default() };
+        let mut follower = source.follow(&query).unwrap();
+        assert!(follower.drain().is_empty());
+
+        source.push(entry(6, "nginx"));
+        source.push(entry(7, "cron"));
+        source.push(entry(8, "nginx"));
+        assert_eq!(cursors(&follower.drain()), ["c6", "c8"]);
+        assert_eq!(source.query(&LogQuery::default()).unwrap().len(), 9);
+    }
+
+    #[test]
+    fn followers_catch_up_from_the_cursor() {
+        let source = MemorySource::new(journal());
+        let query = LogQuery {
+            units: vec!["nginx".to_string()],
+            after_cursor: Some("c0".to_string()),
+            ..Default::default()
+        };
+        let mut follower = source.follow(&query).unwrap();
+        source.push(entry(6, "nginx"));
+
+        assert_eq!(cursors(&follower.drain()), ["c2", "c4", "c6"]);
+        assert!(follower.take_error().is_none());
+    }
+}
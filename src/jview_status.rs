@@ -0,0 +1,235 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use chrono::NaiveDateTime;
+use ratatui::{
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Paragraph, Wrap},
+};
+use crate::jview_config::settings;
+use crate::jview_source::LogSource;
+use crate::jview_theme;
+
+/// How often the status of the highlighted unit is asked again.
+const STATUS_REFRESH: Duration = Duration::from_secs(2);
+
+/// Properties read with `systemctl show`.
+const PROPERTIES: [&str; 13] = [
+    "ActiveState",
+    "SubState",
+    "Result",
+    "MainPID",
+    "MemoryCurrent",
+    "CPUUsageNSec",
+    "TasksCurrent",
+    "NRestarts",
+    "ActiveEnterTimestamp",
+    "ExecMainExitTimestamp",
+    "ExecMainCode",
+    "ExecMainStatus",
+    "FragmentPath",
+];
+
+/// Panel with the state of the unit highlighted in the selector, kept up to date
+/// while it stays highlighted.
+#[derive(Debug)]
+pub struct JviewStatus {
+    source: Arc<dyn LogSource>,
+    unit: Option<String>,                              // Unit the status is of
+    fetched: Option<Instant>,                          // When the status was last asked
+    properties: Result<HashMap<String, String>, String>, // The status, or why it could not be read
+}
+
+impl JviewStatus {
+    pub fn new(source: Arc<dyn LogSource>) -> Self {
+        JviewStatus {
+            source,
+            unit: None,
+            fetched: None,
+            properties: Ok(HashMap::new()),
+        }
+    }
+
+    /// Whether the panel has anything to show. Only the running system has unit states.
+    pub fn is_available(&self) -> bool {
+        self.source.is_live()
+    }
+
+    /// Reads the status again on the next refresh, e.g. after the unit was restarted.
+    pub fn invalidate(&mut self) {
+        self.fetched = None;
+    }
+
+    /// Reads the status again when another unit is highlighted, or when it is
+    /// older than `STATUS_REFRESH`.
+    ///
+    /// # Arguments
+    ///
+    /// * `unit` - Full name of the highlighted unit, `None` when there is none.
+    pub fn refresh(&mut self, unit: Option<&str>) {
+        if !self.is_available() {
+            return;
+        }
+        let stale = self.fetched.is_none_or(|at| at.elapsed() >= STATUS_REFRESH);
+        if self.unit.as_deref() == unit && !stale {
+            return;
+        }
+
+        self.unit = unit.map(|u| u.to_string());
+        self.fetched = Some(Instant::now());
+        self.properties = match unit {
+            Some(unit) => self
+                .source
+                .unit_status(unit, settings::is_user_scope(), &PROPERTIES)
+                .map_err(|e| e.describe()),
+            None => Ok(HashMap::new()),
+        };
+    }
+
+    /// Creates the status panel.
+    ///
+    /// # Arguments
+    ///
+    /// * `selected` - Is the selector currently selected?
+    ///
+    /// # Returns
+    ///
+    /// A `Paragraph` widget with one line per property.
+    pub fn get_status_widget(&self, selected: bool) -> Paragraph<'static> {
+        let title = format!("Status: {}", self.unit.as_deref().unwrap_or("-"));
+        let lines = match (&self.properties, &self.unit) {
+            (_, None) => vec![Line::from("No unit highlighted")],
+            (Err(e), _) => vec![Line::from(Span::styled(e.clone(), Style::default().fg(Color::LightRed)))],
+            (Ok(props), _) => status_lines(props),
+        };
+
+        Paragraph::new(lines)
+            .wrap(Wrap { trim: false })
+            .block(Block::default().borders(Borders::ALL).title(title))
+            .style(get_style(selected))
+    }
+}
+
+/// The status as `Label: value` lines, in the order a look at a misbehaving
+/// service goes: state, restarts, resources, last start and exit.
+fn status_lines(props: &HashMap<String, String>) -> Vec<Line<'static>> {
+    let get = |name: &str| props.get(name).map(String::as_str).unwrap_or_default();
+    let label = |text: &str| Span::styled(format!("{:<9}", text), Style::default().add_modifier(Modifier::BOLD));
+
+    let active = get("ActiveState");
+    let restarts = get("NRestarts");
+    let restart_style = match restarts.parse::<u32>() {
+        Ok(n) if n > 0 => Style::default().fg(Color::LightYellow).add_modifier(Modifier::BOLD),
+        _ => Style::default(),
+    };
+    let result = get("Result");
+    let result_style = match result {
+        "success" | "" => Style::default(),
+        _ => Style::default().fg(Color::LightRed),
+    };
+
+    vec![
+        Line::from(vec![
+            label("State"),
+            Span::styled(
+                format!("{} ({})", or_dash(active), or_dash(get("SubState"))),
+                Style::default().fg(jview_theme::unit_state_color(active)),
+            ),
+        ]),
+        Line::from(vec![label("Result"), Span::styled(or_dash(result).to_string(), result_style)]),
+        Line::from(vec![label("Restarts"), Span::styled(or_dash(restarts).to_string(), restart_style)]),
+        Line::from(vec![label("Main PID"), Span::raw(non_zero(get("MainPID")))]),
+        Line::from(vec![label("Memory"), Span::raw(format_bytes(get("MemoryCurrent")))]),
+        Line::from(vec![label("CPU"), Span::raw(format_nsec(get("CPUUsageNSec")))]),
+        Line::from(vec![label("Tasks"), Span::raw(format_count(get("TasksCurrent")))]),
+        Line::from(vec![label("Started"), Span::raw(format_timestamp(get("ActiveEnterTimestamp")))]),
+        Line::from(vec![label("Exited"), Span::raw(format_timestamp(get("ExecMainExitTimestamp")))]),
+        Line::from(vec![label("Exit"), Span::raw(format_exit(get("ExecMainCode"), get("ExecMainStatus")))]),
+        Line::from(vec![label("File"), Span::raw(or_dash(get("FragmentPath")).to_string())]),
+    ]
+}
+
+fn or_dash(value: &str) -> &str {
+    if value.is_empty() { "-" } else { value }
+}
+
+/// PIDs and the like, where 0 means there is none.
+fn non_zero(value: &str) -> String {
+    match value {
+        "" | "0" => "-".to_string(),
+        _ => value.to_string(),
+    }
+}
+
+/// systemd prints `[not set]` or the largest u64 when accounting is off.
+fn accounted(value: &str) -> Option<u64> {
+    value.parse().ok().filter(|n| *n != u64::MAX)
+}
+
+fn format_count(value: &str) -> String {
+    accounted(value).map_or("-".to_string(), |n| n.to_string())
+}
+
+/// Bytes the way `systemctl status` shows them, like `12.3M`.
+fn format_bytes(value: &str) -> String {
+    let Some(bytes) = accounted(value) else {
+        return "-".to_string();
+    };
+    let mut size = bytes as f64;
+    for unit in ["B", "K", "M", "G"] {
+        if size < 1024.0 {
+            return if unit == "B" { format!("{}B", bytes) } else { format!("{:.1}{}", size, unit) };
+        }
+        size /= 1024.0;
+    }
+    format!("{:.1}T", size)
+}
+
+/// CPU time in nanoseconds as seconds, or minutes and seconds past a minute.
+fn format_nsec(value: &str) -> String {
+    let Some(nsec) = accounted(value) else {
+        return "-".to_string();
+    };
+    let secs = nsec as f64 / 1e9;
+    if secs < 60.0 {
+        format!("{:.3}s", secs)
+    } else {
+        format!("{}min {:.1}s", (secs / 60.0) as u64, secs % 60.0)
+    }
+}
+
+/// A systemd timestamp like `Sat 2026-10-17 12:00:00 UTC` the way log lines
+/// show their time, to fit the panel.
+fn format_timestamp(value: &str) -> String {
+    let mut words = value.split(' ').skip(1);
+    let (Some(date), Some(time)) = (words.next(), words.next()) else {
+        return or_dash(value).to_string();
+    };
+    NaiveDateTime::parse_from_str(&format!("{} {}", date, time), "%Y-%m-%d %H:%M:%S")
+        .map(|t| t.format("%b %d %H:%M:%S").to_string())
+        .unwrap_or_else(|_| value.to_string())
+}
+
+/// The exit status of the main process, with how it ended: the codes are the
+/// `CLD_*` values of waitid(2).
+fn format_exit(code: &str, status: &str) -> String {
+    let how = match code {
+        "1" => "exited",
+        "2" => "killed",
+        "3" => "dumped",
+        _ => return "-".to_string(),
+    };
+    match how {
+        "exited" => format!("{} ({})", status, how),
+        _ => format!("signal {} ({})", status, how),
+    }
+}
+
+fn get_style(selected: bool) -> Style {
+    if selected {
+        Style::default().fg(Color::Cyan).bg(Color::Black)
+    } else {
+        Style::default().fg(Color::Yellow).bg(Color::Blue)
+    }
+}
@@ -0,0 +1,189 @@
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
+
+/// Byte offset of the first grapheme cluster that starts at or after display
+/// column `columns`, for scrolling a line horizontally without cutting a
+/// character in half.
+///
+/// # Arguments
+///
+/// * `line` - The text being scrolled.
+/// * `columns` - How many terminal columns to scroll past.
+///
+/// # Returns
+///
+/// An offset that is always on a character boundary, `line.len()` when the
+/// whole line is scrolled away.
+pub fn skip_columns(line: &str, columns: usize) -> usize {
+    let mut width = 0;
+
+    for (offset, grapheme) in line.grapheme_indices(true) {
+        if width >= columns {
+            return offset;
+        }
+        width += grapheme.width();
+    }
+    line.len()
+}
+
+/// Sorts byte ranges of a line and merges the ones that overlap or touch, so
+/// every highlighted character is styled once.
+pub fn merge_ranges(mut ranges: Vec<(usize, usize)>) -> Vec<(usize, usize)> {
+    ranges.sort();
+
+    let mut merged: Vec<(usize, usize)> = Vec::new();
+    for (start, end) in ranges {
+        match merged.last_mut() {
+            Some(last) if start <= last.1 => last.1 = last.1.max(end),
+            _ => merged.push((start, end)),
+        }
+    }
+    merged
+}
+
+/// Splits a line into rows of at most `columns` display columns.
+///
+/// Rows break at grapheme cluster boundaries, preferably after a space, and at
+/// embedded newlines.
+///
+/// # Returns
+///
+/// The byte ranges of the rows, at least one even for an empty line.
+pub fn wrap_columns(line: &str, columns: usize) -> Vec<(usize, usize)> {
+    let columns = columns.max(1);
+    let mut rows = Vec::new();
+    let mut start = 0;
+    let mut width = 0;
+    let mut last_space = None; // Where the row can be broken after a space
+
+    for (offset, grapheme) in line.grapheme_indices(true) {
+        if grapheme == "\n" || grapheme == "\r\n" {
+            rows.push((start, offset));
+            start = offset + grapheme.len();
+            width = 0;
+            last_space = None;
+            continue;
+        }
+
+        let grapheme_width = grapheme.width();
+        if width + grapheme_width > columns && offset > start {
+            let end = last_space.unwrap_or(offset);
+            rows.push((start, end));
+            width = line[end..offset].width();
+            start = end;
+            last_space = None;
+        }
+        width += grapheme_width;
+        if grapheme == " " {
+            last_space = Some(offset + 1);
+        }
+    }
+
+    rows.push((start, line.len()));
+    rows
+}
+
+/// Scores how well `pattern` matches `text` as a fuzzy subsequence, ignoring case.
+///
+/// Every character of the pattern must appear in the text in order. Matches
+/// score more when they are consecutive or start a word (after `-`, `.`, `_`,
+/// `@` or at the start), and less for each character skipped in between.
+///
+/// # Returns
+///
+/// The score, higher is better, and the byte offsets of the matched characters
+/// of `text`, or `None` when the pattern does not match.
+pub fn fuzzy_match(pattern: &str, text: &str) -> Option<(i64, Vec<usize>)> {
+    let mut wanted = pattern.chars().flat_map(char::to_lowercase).peekable();
+    let mut positions = Vec::new();
+    let mut score = 0;
+    let mut previous: Option<char> = None;
+    let mut last_match: Option<usize> = None; // Char index of the last matched character
+
+    for (n, (offset, c)) in text.char_indices().enumerate() {
+        let Some(&next) = wanted.peek() else { break };
+        if c.to_lowercase().eq(std::iter::once(next)) {
+            score += 16;
+            if previous.is_none_or(|p| matches!(p, '-' | '.' | '_' | '@')) {
+                score += 8; // Start of a word
+            }
+            match last_match {
+                Some(last) if last + 1 == n => score += 8, // Consecutive
+                Some(last) => score -= (n - last - 1) as i64,
+                None => score -= n as i64,
+            }
+            positions.push(offset);
+            last_match = Some(n);
+            wanted.next();
+        }
+        previous = Some(c);
+    }
+
+    wanted.peek().is_none().then_some((score, positions))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rows(line: &str, columns: usize) -> Vec<&str> {
+        wrap_columns(line, columns).into_iter().map(|(start, end)| &line[start..end]).collect()
+    }
+
+    #[test]
+    fn skip_columns_counts_display_width() {
+        assert_eq!(skip_columns("abc", 2), 2);
+        assert_eq!(skip_columns("abc", 9), 3);
+        // Wide characters take two columns and are never cut in half
+        assert_eq!(skip_columns("日本語", 2), "日".len());
+        assert_eq!(skip_columns("日本語", 3), "日本".len());
+        assert_eq!(skip_columns("e\u{301}x", 1), "e\u{301}".len());
+    }
+
+    #[test]
+    fn wrap_breaks_after_spaces() {
+        assert_eq!(rows("the quick brown fox", 10), ["the quick ", "brown fox"]);
+        assert_eq!(rows("short", 10), ["short"]);
+    }
+
+    #[test]
+    fn wrap_cuts_words_longer_than_a_row() {
+        assert_eq!(rows("abcdefghij", 4), ["abcd", "efgh", "ij"]);
+    }
+
+    #[test]
+    fn wrap_keeps_wide_characters_whole() {
+        assert_eq!(rows("日本語テキスト", 5), ["日本", "語テ", "キス", "ト"]);
+        assert_eq!(rows("ab\u{1F600}cd", 3), ["ab", "\u{1F600}c", "d"]);
+    }
+
+    #[test]
+    fn wrap_breaks_at_newlines() {
+        assert_eq!(rows("one\ntwo\r\nthree", 20), ["one", "two", "three"]);
+        assert_eq!(rows("", 20), [""]);
+        assert_eq!(rows("x", 0), ["x"]);
+    }
+
+    #[test]
+    fn fuzzy_matches_subsequences_ignoring_case() {
+        assert_eq!(fuzzy_match("ngx", "nginx").map(|(_, p)| p), Some(vec![0, 1, 4]));
+        assert!(fuzzy_match("NGX", "nginx").is_some());
+        assert!(fuzzy_match("", "nginx").is_some());
+        assert!(fuzzy_match("xng", "nginx").is_none());
+        assert!(fuzzy_match("nginxx", "nginx").is_none());
+    }
+
+    #[test]
+    fn fuzzy_prefers_consecutive_and_word_start_matches() {
+        let score = |pattern, text| fuzzy_match(pattern, text).unwrap().0;
+        assert!(score("ssh", "sshd") > score("ssh", "system-shutdown"));
+        assert!(score("cron", "cron") > score("cron", "anacron"));
+        assert!(score("sd", "systemd-sd") < score("sd", "sd-daemon"));
+    }
+
+    #[test]
+    fn fuzzy_positions_are_byte_offsets() {
+        let (_, positions) = fuzzy_match("ü2", "über2").unwrap();
+        assert_eq!(positions, [0, "über".len()]);
+    }
+}
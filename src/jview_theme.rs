@@ -0,0 +1,156 @@
+use std::str::FromStr;
+use ratatui::style::{Color, Modifier, Style};
+use crate::jview_query::{parse_priority, PRIORITY_NAMES};
+
+/// Colors of log lines by syslog priority, 0 (emerg) to 7 (debug).
+///
+/// The styles are patched over the pane colors, so a priority without a color of
+/// its own keeps the look of the pane.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PriorityTheme {
+    styles: [Style; 8],
+}
+
+impl Default for PriorityTheme {
+    fn default() -> Self {
+        let bold = Style::default().add_modifier(Modifier::BOLD);
+        PriorityTheme {
+            styles: [
+                bold.fg(Color::White).bg(Color::Red), // emerg
+                bold.fg(Color::White).bg(Color::Red), // alert
+                bold.fg(Color::LightRed),             // crit
+                Style::default().fg(Color::LightRed), // err
+                bold.fg(Color::LightYellow),          // warning
+                bold,                                 // notice
+                Style::default(),                     // info
+                Style::default().fg(Color::DarkGray), // debug
+            ],
+        }
+    }
+}
+
+impl PriorityTheme {
+    /// Parses a theme like `err=red,warning=yellow:bold,emerg=white:red:bold`.
+    ///
+    /// Each priority, given by name or number, takes a foreground color, an
+    /// optional background color and any of the modifiers `bold`, `dim`, `italic`,
+    /// `underline` and `reverse`. Colors are names like `lightred`, indexes like
+    /// `208` or hex like `#ff8700`. Priorities that are not listed keep their
+    /// default style.
+    ///
+    /// # Arguments
+    ///
+    /// * `spec` - The theme as given on the command line.
+    ///
+    /// # Returns
+    ///
+    /// The theme, or a message describing the first invalid part.
+    pub fn parse(spec: &str) -> Result<Self, String> {
+        let mut theme = PriorityTheme::default();
+
+        for part in spec.split(',').map(str::trim).filter(|p| !p.is_empty()) {
+            let (name, value) = part
+                .split_once('=')
+                .ok_or_else(|| format!("expected priority=color in '{}'", part))?;
+            let priority = parse_priority(name.trim())
+                .ok_or_else(|| format!("unknown priority '{}', expected one of {}", name, PRIORITY_NAMES.join(", ")))?;
+
+            let mut style = Style::default();
+            let mut colors = 0;
+            for word in value.split(':').map(str::trim) {
+                style = match word {
+                    "bold" => style.add_modifier(Modifier::BOLD),
+                    "dim" => style.add_modifier(Modifier::DIM),
+                    "italic" => style.add_modifier(Modifier::ITALIC),
+                    "underline" => style.add_modifier(Modifier::UNDERLINED),
+                    "reverse" => style.add_modifier(Modifier::REVERSED),
+                    _ => {
+                        let color = Color::from_str(word).map_err(|_| format!("unknown color '{}'", word))?;
+                        colors += 1;
+                        match colors {
+                            1 => style.fg(color),
+                            2 => style.bg(color),
+                            _ => return Err(format!("too many colors in '{}'", part)),
+                        }
+                    }
+                };
+            }
+            theme.styles[priority as usize] = style;
+        }
+
+        Ok(theme)
+    }
+
+    /// Style of lines with the given priority; entries without one are left alone.
+    pub fn style(&self, priority: Option<u8>) -> Style {
+        priority
+            .and_then(|p| self.styles.get(p as usize))
+            .copied()
+            .unwrap_or_default()
+    }
+}
+
+/// Colors of the tags that tell apart the units shown together, in name order.
+const UNIT_COLORS: [Color; 8] = [
+    Color::LightCyan,
+    Color::LightMagenta,
+    Color::LightGreen,
+    Color::LightYellow,
+    Color::LightBlue,
+    Color::LightRed,
+    Color::Cyan,
+    Color::Magenta,
+];
+
+/// Color of the `n`th of the units shown together, repeating after eight units.
+pub fn unit_color(n: usize) -> Color {
+    UNIT_COLORS[n % UNIT_COLORS.len()]
+}
+
+/// Color of the state badge of a unit, by its `ActiveState`.
+pub fn unit_state_color(active: &str) -> Color {
+    match active {
+        "failed" => Color::LightRed,
+        "active" => Color::LightGreen,
+        "activating" | "deactivating" | "reloading" | "refreshing" => Color::LightYellow,
+        _ => Color::DarkGray,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn listed_priorities_replace_their_default() {
+        let theme = PriorityTheme::parse("err=red, 4=#ff8700:bold,debug=208:black:dim:italic").unwrap();
+        let default = PriorityTheme::default();
+
+        assert_eq!(theme.style(Some(3)), Style::default().fg(Color::Red));
+        assert_eq!(theme.style(Some(4)), Style::default().fg(Color::Rgb(0xff, 0x87, 0)).add_modifier(Modifier::BOLD));
+        assert_eq!(
+            theme.style(Some(7)),
+            Style::default()
+                .fg(Color::Indexed(208))
+                .bg(Color::Black)
+                .add_modifier(Modifier::DIM | Modifier::ITALIC)
+        );
+        assert_eq!(theme.style(Some(0)), default.style(Some(0)));
+        assert_eq!(PriorityTheme::parse("").unwrap(), default);
+    }
+
+    #[test]
+    fn entries_without_a_priority_keep_the_pane_style() {
+        let theme = PriorityTheme::default();
+        assert_eq!(theme.style(None), Style::default());
+        assert_eq!(theme.style(Some(9)), Style::default());
+    }
+
+    #[test]
+    fn invalid_parts_are_named_in_the_error() {
+        assert_eq!(PriorityTheme::parse("err").unwrap_err(), "expected priority=color in 'err'");
+        assert!(PriorityTheme::parse("loud=red").unwrap_err().starts_with("unknown priority 'loud'"));
+        assert_eq!(PriorityTheme::parse("err=mauve").unwrap_err(), "unknown color 'mauve'");
+        assert_eq!(PriorityTheme::parse("err=red:blue:green").unwrap_err(), "too many colors in 'err=red:blue:green'");
+    }
+}
@@ -0,0 +1,175 @@
+use chrono::{DateTime, Duration, Local, NaiveDate, NaiveDateTime, NaiveTime, TimeZone};
+
+/// Absolute formats accepted for `--since` and `--until`, as in journalctl.
+const DATE_TIME_FORMATS: [&str; 4] = ["%Y-%m-%d %H:%M:%S", "%Y-%m-%d %H:%M", "%Y-%m-%dT%H:%M:%S", "%Y-%m-%dT%H:%M"];
+const TIME_FORMATS: [&str; 2] = ["%H:%M:%S", "%H:%M"];
+
+/// Parses a timestamp the way journalctl does for `--since` and `--until`.
+///
+/// Accepts `now`, `today`, `yesterday`, `tomorrow`, `@<unix seconds>`, dates like
+/// `2024-05-01` or `2024-05-01 13:30[:00]`, a time of today like `13:30`, and
+/// relative times like `-1h`, `+30min` or `2 days ago`.
+///
+/// # Arguments
+///
+/// * `text` - The timestamp as typed by the user.
+///
+/// # Returns
+///
+/// Microseconds since the epoch, or `None` if the text is not a valid timestamp.
+pub fn parse_time(text: &str) -> Option<i64> {
+    let text = text.trim();
+    let now = Local::now();
+    let today = now.date_naive();
+
+    let time = match text {
+        "now" => Some(now),
+        "today" => local_midnight(today),
+        "yesterday" => local_midnight(today.pred_opt()?),
+        "tomorrow" => local_midnight(today.succ_opt()?),
+        _ => None,
+    };
+    if let Some(time) = time {
+        return Some(time.timestamp_micros());
+    }
+
+    if let Some(seconds) = text.strip_prefix('@') {
+        let seconds: f64 = seconds.parse().ok()?;
+        return Some((seconds * 1_000_000.0) as i64);
+    }
+
+    // Spans reaching past the dates chrono knows are not valid timestamps
+    if let Some(ago) = text.strip_suffix("ago") {
+        return Some(now.checked_sub_signed(parse_span(ago)?)?.timestamp_micros());
+    }
+    if let Some(span) = text.strip_prefix('-') {
+        return Some(now.checked_sub_signed(parse_span(span)?)?.timestamp_micros());
+    }
+    if let Some(span) = text.strip_prefix('+') {
+        return Some(now.checked_add_signed(parse_span(span)?)?.timestamp_micros());
+    }
+
+    let naive = DATE_TIME_FORMATS
+        .iter()
+        .find_map(|f| NaiveDateTime::parse_from_str(text, f).ok())
+        .or_else(|| NaiveDate::parse_from_str(text, "%Y-%m-%d").ok().and_then(|d| d.and_hms_opt(0, 0, 0)))
+        .or_else(|| {
+            TIME_FORMATS
+                .iter()
+                .find_map(|f| NaiveTime::parse_from_str(text, f).ok())
+                .map(|t| today.and_time(t))
+        })?;
+    Local.from_local_datetime(&naive).earliest().map(|t| t.timestamp_micros())
+}
+
+fn local_midnight(day: NaiveDate) -> Option<DateTime<Local>> {
+    Local.from_local_datetime(&day.and_hms_opt(0, 0, 0)?).earliest()
+}
+
+/// Parses a time span like `1h 30min`, `2 days` or `90s`. Spans too long to
+/// represent are rejected.
+fn parse_span(text: &str) -> Option<Duration> {
+    let mut total = Duration::zero();
+    let mut rest = text.trim();
+
+    if rest.is_empty() {
+        return None;
+    }
+    while !rest.is_empty() {
+        let digits = rest.find(|c: char| !c.is_ascii_digit()).unwrap_or(rest.len());
+        let amount = i32::try_from(rest[..digits].parse::<i64>().ok()?).ok()?;
+        rest = rest[digits..].trim_start();
+
+        let letters = rest.find(|c: char| !c.is_ascii_alphabetic()).unwrap_or(rest.len());
+        let unit = span_unit(&rest[..letters])?;
+        rest = rest[letters..].trim_start();
+
+        total = total.checked_add(&unit.checked_mul(amount)?)?;
+    }
+    Some(total)
+}
+
+/// Length of one time span unit, using the systemd names (`m` is a minute, `M` a month).
+fn span_unit(unit: &str) -> Option<Duration> {
+    let span = match unit {
+        "us" | "usec" => Duration::microseconds(1),
+        "ms" | "msec" => Duration::milliseconds(1),
+        "" | "s" | "sec" | "second" | "seconds" => Duration::seconds(1),
+        "m" | "min" | "minute" | "minutes" => Duration::minutes(1),
+        "h" | "hr" | "hour" | "hours" => Duration::hours(1),
+        "d" | "day" | "days" => Duration::days(1),
+        "w" | "week" | "weeks" => Duration::weeks(1),
+        "M" | "month" | "months" => Duration::seconds(2_629_800),
+        "y" | "year" | "years" => Duration::seconds(31_557_600),
+        _ => return None,
+    };
+    Some(span)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn local_usec(text: &str) -> i64 {
+        let naive = NaiveDateTime::parse_from_str(text, "%Y-%m-%d %H:%M:%S").unwrap();
+        Local.from_local_datetime(&naive).earliest().unwrap().timestamp_micros()
+    }
+
+    /// How far `text` resolves from now, in seconds.
+    fn offset(text: &str) -> i64 {
+        (parse_time(text).unwrap() - Local::now().timestamp_micros()) / 1_000_000
+    }
+
+    #[test]
+    fn absolute_dates_are_local_time() {
+        assert_eq!(parse_time("2024-05-01"), Some(local_usec("2024-05-01 00:00:00")));
+        assert_eq!(parse_time("2024-05-01 13:30"), Some(local_usec("2024-05-01 13:30:00")));
+        assert_eq!(parse_time(" 2024-05-01T13:30:15 "), Some(local_usec("2024-05-01 13:30:15")));
+    }
+
+    #[test]
+    fn at_sign_takes_unix_seconds() {
+        assert_eq!(parse_time("@1700000000"), Some(1_700_000_000_000_000));
+        assert_eq!(parse_time("@1.5"), Some(1_500_000));
+    }
+
+    #[test]
+    fn relative_times_count_from_now() {
+        assert!((-3601..=-3599).contains(&offset("-1h")));
+        assert!((1799..=1801).contains(&offset("+30min")));
+        assert!((-172_801..=-172_799).contains(&offset("2 days ago")));
+        assert!((-5401..=-5399).contains(&offset("-1h 30m")));
+    }
+
+    #[test]
+    fn day_names_are_midnight() {
+        let today = parse_time("today").unwrap();
+        let hours = (today - parse_time("yesterday").unwrap()) / 3_600_000_000;
+        assert!((23..=25).contains(&hours));
+        assert!(parse_time("tomorrow").unwrap() > parse_time("now").unwrap());
+        assert!(today <= parse_time("now").unwrap());
+    }
+
+    #[test]
+    fn span_units_follow_systemd() {
+        assert_eq!(parse_span("90s"), Some(Duration::seconds(90)));
+        assert_eq!(parse_span("1m"), Some(Duration::minutes(1)));
+        assert_eq!(parse_span("1M"), Some(Duration::seconds(2_629_800)));
+        assert_eq!(parse_span("1w 2d"), Some(Duration::days(9)));
+    }
+
+    #[test]
+    fn overflowing_spans_are_rejected() {
+        assert_eq!(parse_span("99999999999s"), None);
+        assert_eq!(parse_span("2000000000y"), None);
+        assert_eq!(parse_time("-300000y"), None);
+        assert_eq!(parse_time("+300000y"), None);
+    }
+
+    #[test]
+    fn garbage_is_rejected() {
+        for text in ["", "soon", "-", "-1 fortnight", "2024-13-01", "@abc", "25:00"] {
+            assert_eq!(parse_time(text), None, "{:?}", text);
+        }
+    }
+}
@@ -0,0 +1,204 @@
+use ratatui::{
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, List, ListItem, Paragraph},
+};
+use crossterm::event::{KeyCode, KeyEvent};
+use crate::jview_config::settings;
+use crate::jview_prompt::{JviewPrompt, PromptAction};
+use crate::jview_time;
+
+/// A choice in the time range picker.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Choice {
+    Since(&'static str), // Everything after a time, until now
+    ThisBoot,            // Everything since the current boot
+    AllTime,             // No time window at all
+    CustomSince,         // Ask for the lower bound
+    CustomUntil,         // Ask for the upper bound
+}
+
+/// What the picker offers, in order, with the label shown for it.
+const CHOICES: [(&str, Choice); 9] = [
+    ("Last 5 minutes", Choice::Since("-5m")),
+    ("Last hour", Choice::Since("-1h")),
+    ("Last 24 hours", Choice::Since("-24h")),
+    ("Since yesterday", Choice::Since("yesterday")),
+    ("Today", Choice::Since("today")),
+    ("This boot", Choice::ThisBoot),
+    ("All time", Choice::AllTime),
+    ("Since...", Choice::CustomSince),
+    ("Until...", Choice::CustomUntil),
+];
+
+/// Popup to pick the time window of the logs pane from presets or typed bounds.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JviewTimeRange {
+    selected_idx: usize,
+    prompt: Option<(Choice, JviewPrompt)>, // Bound being typed after picking Since or Until
+}
+
+impl JviewTimeRange {
+    pub fn new() -> Self {
+        // Start on the window in use, when it is one of the presets
+        let since = settings::get_since();
+        let selected_idx = CHOICES
+            .iter()
+            .position(|(_, choice)| match choice {
+                Choice::Since(s) => since.as_deref() == Some(*s) && settings::get_until().is_none(),
+                _ => false,
+            })
+            .unwrap_or(0);
+
+        JviewTimeRange {
+            selected_idx,
+            prompt: None,
+        }
+    }
+
+    /// Handles a key while the picker is open.
+    ///
+    /// # Returns
+    ///
+    /// `false` once the picker should be closed.
+    pub fn navigate(&mut self, key: KeyEvent) -> bool {
+        if let Some((choice, prompt)) = self.prompt.as_mut() {
+            match prompt.handle_key(key) {
+                PromptAction::Edit => {}
+                PromptAction::Cancel => self.prompt = None,
+                PromptAction::Submit => {
+                    let text = prompt.input().trim().to_string();
+                    if !text.is_empty() && jview_time::parse_time(&text).is_none() {
+                        prompt.set_error(&format!("Invalid time '{}'", text));
+                        return true;
+                    }
+
+                    // An empty bound opens that side of the window
+                    let bound = (!text.is_empty()).then_some(text.as_str());
+                    let (since, until) = (settings::get_since(), settings::get_until());
+                    if *choice == Choice::CustomSince {
+                        settings::set_time_range(bound, until.as_deref());
+                    } else {
+                        settings::set_time_range(since.as_deref(), bound);
+                    }
+                    return false;
+                }
+            }
+            return true;
+        }
+
+        match key.code {
+            KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('T') => return false,
+            KeyCode::Up if self.selected_idx > 0 => self.selected_idx -= 1,
+            KeyCode::Down if self.selected_idx + 1 < CHOICES.len() => self.selected_idx += 1,
+            KeyCode::Enter => return self.pick(CHOICES[self.selected_idx].1),
+            _ => {}
+        }
+        true
+    }
+
+    /// Applies a choice, or asks for the bound of a custom one.
+    ///
+    /// # Returns
+    ///
+    /// `false` when the picker is done.
+    fn pick(&mut self, choice: Choice) -> bool {
+        match choice {
+            Choice::Since(since) => {
+                settings::set_time_range(Some(since), None);
+                settings::set_boot(None);
+            }
+            Choice::ThisBoot => {
+                settings::set_time_range(None, None);
+                settings::set_boot(Some("0"));
+            }
+            Choice::AllTime => {
+                settings::set_time_range(None, None);
+                settings::set_boot(None);
+            }
+            Choice::CustomSince | Choice::CustomUntil => {
+                let label = if choice == Choice::CustomSince { "Since" } else { "Until" };
+                let label = format!("{} (e.g. 14:30, 2h ago, 2024-05-01 08:00, empty for none)", label);
+                self.prompt = Some((choice, JviewPrompt::new(&label)));
+                return true;
+            }
+        }
+        false
+    }
+
+    /// Creates the picker widget.
+    ///
+    /// # Returns
+    ///
+    /// A `List` widget with the presets, `None` while a bound is being typed.
+    pub fn get_widget(&self) -> Option<List<'static>> {
+        if self.prompt.is_some() {
+            return None;
+        }
+
+        let items: Vec<ListItem> = CHOICES
+            .iter()
+            .enumerate()
+            .map(|(i, (label, _))| {
+                if i == self.selected_idx {
+                    ListItem::new(*label).style(Style::default().fg(Color::Black).bg(Color::Cyan))
+                } else {
+                    ListItem::new(*label)
+                }
+            })
+            .collect();
+
+        Some(
+            List::new(items)
+                .block(Block::default().borders(Borders::ALL).title("Time range [Enter]: Pick | [Esc]: Close"))
+                .style(Style::default().fg(Color::White).bg(Color::Black)),
+        )
+    }
+
+    /// The prompt for a typed bound, while it is open.
+    pub fn get_prompt_widget(&self) -> Option<Paragraph<'static>> {
+        self.prompt.as_ref().map(|(_, prompt)| prompt.get_prompt_widget())
+    }
+}
+
+/// Describes the window the logs are shown for, in the words of the presets
+/// when it is one of them.
+fn window_label() -> String {
+    let (since, until, boot) = (settings::get_since(), settings::get_until(), settings::get_boot());
+    let preset = CHOICES.iter().find(|(_, choice)| match choice {
+        Choice::Since(s) => since.as_deref() == Some(*s),
+        _ => false,
+    });
+
+    let window = match (preset, &since, &until) {
+        (Some((label, _)), _, None) => label.to_string(),
+        (_, None, None) => String::new(),
+        (_, since, until) => format!(
+            "{} .. {}",
+            since.as_deref().unwrap_or("start"),
+            until.as_deref().unwrap_or("now")
+        ),
+    };
+
+    match (boot.as_deref(), window.is_empty()) {
+        (None, true) => "All time".to_string(),
+        (None, false) => window,
+        (Some("0"), true) => "This boot".to_string(),
+        (Some(boot), true) => format!("Boot {}", boot),
+        (Some(boot), false) => format!("Boot {}, {}", boot, window),
+    }
+}
+
+/// Creates the header line shown above the panes.
+///
+/// # Returns
+///
+/// A `Paragraph` widget with the time window of the logs.
+pub fn get_header_widget() -> Paragraph<'static> {
+    Paragraph::new(Line::from(vec![
+        Span::styled(" journalview ", Style::default().fg(Color::Black).bg(Color::Cyan).add_modifier(Modifier::BOLD)),
+        Span::raw(" Time: "),
+        Span::styled(window_label(), Style::default().add_modifier(Modifier::BOLD)),
+        Span::raw(" [T]: Change"),
+    ]))
+}
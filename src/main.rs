@@ -1,13 +1,18 @@
 use ratatui::{
     backend::CrosstermBackend,
     layout::{Constraint, Direction, Layout},
+    widgets::Clear,
     Terminal,
 };
 use crossterm::{
+    cursor,
     execute,
     terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
 };
-use std::io;
+use std::io::{self, Stdout};
+use std::panic;
+use std::process;
+use clap::Parser;
 
 mod jview_screen;
 mod jview_logs;
@@ -16,6 +21,26 @@ mod jview_selector;
 mod jview_help;
 mod jview_config;
 mod jview_debug;
+mod jview_entry;
+mod jview_filter;
+mod jview_follow;
+mod jview_journal_file;
+mod jview_query;
+mod jview_source;
+mod jview_journalctl;
+mod jview_export;
+mod jview_cli;
+mod jview_time;
+mod jview_print;
+mod jview_detail;
+mod jview_theme;
+mod jview_text;
+mod jview_prompt;
+mod jview_history;
+mod jview_timerange;
+mod jview_boots;
+mod jview_status;
+mod jview_error;
 
 use crate::jview_screen::UiScreen;
 use crate::jview_screen::UiSection::Search;
@@ -24,16 +49,51 @@ use crate::jview_screen::UiSection::Selector;
 //use crate::jview_screen::UiSection::Help;
 
 fn main() -> Result<(), io::Error> {
+    let cli = jview_cli::Cli::parse();
+    let source = cli.open_source().unwrap_or_else(|e| {
+        eprintln!("journalview: {}", e.describe());
+        process::exit(1);
+    });
+    cli.seed_settings(source.as_ref());
+
+    if cli.print {
+        if let Err(e) = jview_print::print_logs(source.as_ref(), cli.output, cli.follow) {
+            eprintln!("journalview: {}", e.describe());
+            process::exit(1);
+        }
+        return Ok(());
+    }
+
+    // A panic must not leave the terminal in raw mode on the alternate screen
+    let default_hook = panic::take_hook();
+    panic::set_hook(Box::new(move |info| {
+        restore_terminal();
+        default_hook(info);
+    }));
+
     enable_raw_mode()?;
     let mut stdout = io::stdout();
     execute!(stdout, EnterAlternateScreen)?;
     let backend = CrosstermBackend::new(stdout);
     let mut terminal = Terminal::new(backend)?;
-    let mut screen = UiScreen::new(); // Persistent screen state
+    let mut screen = UiScreen::new(source); // Persistent screen state
+    cli.seed_screen(&mut screen);
 
     jview_debug::init_debug_log();
     jview_debug::log_debug_info("Starting journalview", format_args!(""));
+    let result = run(&mut terminal, &mut screen);
+
+    restore_terminal();
+    terminal.show_cursor()?;
+    result
+}
+
+/// Draws the screen and handles keys until the user quits.
+fn run(terminal: &mut Terminal<CrosstermBackend<Stdout>>, screen: &mut UiScreen) -> Result<(), io::Error> {
     loop {
+        screen.finish_unit_action();
+        screen.refresh_logs();
+        screen.refresh_unit_status();
         terminal.draw(|f| {
             // Define the layout with two main sections: Left and Right
             let overall_layout = Layout::default()
@@ -56,7 +116,9 @@ fn main() -> Result<(), io::Error> {
             let selection_chunks = Layout::default()
                 .direction(Direction::Vertical)
                 .constraints([
-                    Constraint::Percentage(100),
+                    Constraint::Length(3), // Unit filter
+                    Constraint::Min(5),    // Unit list
+                    Constraint::Length(if screen.has_unit_status() { 14 } else { 0 }), // Unit status, the unit file may take two lines
                 ])
                 .split(active_layout[0]);
 
@@ -74,10 +136,19 @@ fn main() -> Result<(), io::Error> {
                 .constraints([Constraint::Min(1)]) // Only one section (Help) that takes the entire space
                 .split(overall_layout[2])[0]; // Apply to the whole width
 
+            // Header with the time window of the logs, in the top margin
+            f.render_widget(jview_timerange::get_header_widget(), overall_layout[0]);
+
             // Draw the left column selector
-            screen.set_selector_max_height(selection_chunks[0].height as usize);
+            screen.set_selector_max_height(selection_chunks[1].height as usize);
             let selwidget = screen.get_selector_widget(screen.get_selected() == Selector);
-            f.render_widget(selwidget, selection_chunks[0]);
+            let filter_widget = screen.get_unit_filter_widget(screen.get_selected() == Selector);
+            f.render_widget(filter_widget, selection_chunks[0]);
+            f.render_widget(selwidget, selection_chunks[1]);
+            if screen.has_unit_status() {
+                let status_widget = screen.get_unit_status_widget(screen.get_selected() == Selector);
+                f.render_widget(status_widget, selection_chunks[2]);
+            }
 
             // Search Section
             let search_widget = screen.get_search_widget(screen.get_selected() == Search);
@@ -87,21 +158,73 @@ fn main() -> Result<(), io::Error> {
             //let mut log_items: Vec<ListItem> = jview_logs::get_log_items(0, viewer_chunks[1].height as usize, 0, screen.get_selected() == Logs);
             //let mut log_items: Vec<ListItem> = jview_logs::get_log_items(vertical_offset, viewer_chunks[1].height as usize, horizontal_offset, screen.get_selected() == Logs);
             screen.set_logs_max_height(viewer_chunks[1].height as usize);
+            screen.set_logs_max_width(viewer_chunks[1].width as usize);
             let logs_widget = screen.get_logs_widget(screen.get_selected() == Logs);
             f.render_widget(logs_widget, viewer_chunks[1]);
+            if let Some(prompt_widget) = screen.get_prompt_widget() {
+                let prompt_area = jview_prompt::prompt_area(viewer_chunks[1]);
+                f.render_widget(Clear, prompt_area);
+                f.render_widget(prompt_widget, prompt_area);
+            }
+
+            // Entry detail popup, drawn over everything else
+            let detail_area = jview_detail::popup_area(f.area(), 80, 80);
+            screen.set_detail_max_height(detail_area.height as usize);
+            if let Some(detail_widget) = screen.get_detail_widget() {
+                f.render_widget(Clear, detail_area);
+                f.render_widget(detail_widget, detail_area);
+            }
+
+            // Boot list, wide enough for the boot IDs and both timestamps
+            let boots_area = jview_detail::popup_area(f.area(), 90, 60);
+            screen.set_boots_max_height(boots_area.height as usize);
+            if let Some(boots_widget) = screen.get_boots_widget() {
+                f.render_widget(Clear, boots_area);
+                f.render_widget(boots_widget, boots_area);
+            }
+
+            // Time range picker, with room for every preset
+            if let Some(time_range_widget) = screen.get_time_range_widget() {
+                let time_range_area = jview_detail::popup_area(f.area(), 40, 50);
+                f.render_widget(Clear, time_range_area);
+                f.render_widget(time_range_widget, time_range_area);
+            }
+
+            // Saved queries picker, opened from the search box
+            if let Some(picker_widget) = screen.get_picker_widget() {
+                let picker_area = jview_detail::popup_area(f.area(), 60, 50);
+                f.render_widget(Clear, picker_area);
+                f.render_widget(picker_widget, picker_area);
+            }
+
+            // Why the logs could not be read, until dismissed or retried
+            if let Some(error_widget) = screen.get_error_widget() {
+                let error_area = jview_detail::popup_area(f.area(), 60, 30);
+                f.render_widget(Clear, error_area);
+                f.render_widget(error_widget, error_area);
+            }
+
+            // Confirmation of an action on a unit
+            if let Some(confirm_widget) = screen.get_confirm_widget() {
+                let confirm_area = jview_detail::popup_area(f.area(), 40, 20);
+                f.render_widget(Clear, confirm_area);
+                f.render_widget(confirm_widget, confirm_area);
+            }
 
             // Help Section
-            let help_widget = jview_help::get_widget();
+            let help_widget = jview_help::get_widget(screen.get_selected());
             f.render_widget(help_widget, help_chunk);
 
         })?;
-        if jview_screen::screen_navigate(&mut screen)? == true {
+        if jview_screen::screen_navigate(screen)? {
             break;
         }
     }
-
-    disable_raw_mode()?;
-    execute!(terminal.backend_mut(), LeaveAlternateScreen)?;
-    terminal.show_cursor()?;
     Ok(())
 }
+
+/// Leaves raw mode and the alternate screen, also after a panic or an error.
+fn restore_terminal() {
+    let _ = disable_raw_mode();
+    let _ = execute!(io::stdout(), LeaveAlternateScreen, cursor::Show);
+}